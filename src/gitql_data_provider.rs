@@ -1,3 +1,10 @@
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use gitql_core::object::Row;
 
 use gitql_core::values::base::Value;
@@ -9,60 +16,183 @@ use gitql_core::values::text::TextValue;
 use gitql_engine::data_provider::DataProvider;
 
 use gix::refs::Category;
+use nu_protocol::Signals;
+
+/// Error returned by the providers when Nushell asked to interrupt the query.
+pub const INTERRUPTED_ERROR: &str = "Query interrupted";
+
+/// Notes reference read by the `notes` table unless another one is configured.
+pub const DEFAULT_NOTES_REF: &str = "refs/notes/commits";
+
+/// The commits walked by the `commits` and `diffs` tables.
+#[derive(Clone, Default)]
+pub enum WalkScope {
+    /// Commits reachable from HEAD
+    #[default]
+    Head,
+    /// Commits reachable from any local branch
+    Branches,
+    /// Commits reachable from HEAD or any reference
+    All,
+    /// Commits selected by user given revspecs, like `main..feature` or
+    /// `^v1.0 HEAD`
+    Revspec(String),
+}
+
+impl WalkScope {
+    /// `head`, `branches` and `all` select a scope, anything else is a revspec.
+    pub fn from_flag(value: &str) -> Self {
+        match value {
+            "head" => WalkScope::Head,
+            "branches" => WalkScope::Branches,
+            "all" => WalkScope::All,
+            revspec => WalkScope::Revspec(revspec.to_string()),
+        }
+    }
+}
+
+/// Time spent in `GitDataProvider::provide` and the number of rows provided per
+/// table, collected for the `--analysis` output.
+#[derive(Default)]
+pub struct ProviderStats {
+    pub provide_duration: Duration,
+    pub table_rows: Vec<(String, usize)>,
+}
 
 pub struct GitDataProvider {
     pub repos: Vec<gix::Repository>,
+    pub stats: Arc<Mutex<ProviderStats>>,
+    pub signals: Signals,
+    pub blame_paths: Vec<String>,
+    pub notes_ref: String,
+    pub walk: WalkScope,
 }
 
 impl GitDataProvider {
     pub fn new(repos: Vec<gix::Repository>) -> Self {
-        Self { repos }
+        Self {
+            repos,
+            stats: Arc::default(),
+            signals: Signals::empty(),
+            blame_paths: vec![],
+            notes_ref: DEFAULT_NOTES_REF.to_string(),
+            walk: WalkScope::default(),
+        }
+    }
+
+    pub fn with_walk(mut self, walk: WalkScope) -> Self {
+        self.walk = walk;
+        self
+    }
+
+    pub fn with_notes_ref(mut self, notes_ref: String) -> Self {
+        self.notes_ref = notes_ref;
+        self
+    }
+
+    pub fn with_blame_paths(mut self, blame_paths: Vec<String>) -> Self {
+        self.blame_paths = blame_paths;
+        self
+    }
+
+    pub fn with_signals(mut self, signals: Signals) -> Self {
+        self.signals = signals;
+        self
+    }
+
+    pub fn with_stats(mut self, stats: Arc<Mutex<ProviderStats>>) -> Self {
+        self.stats = stats;
+        self
     }
 }
 
 impl DataProvider for GitDataProvider {
     fn provide(&self, table: &str, selected_columns: &[String]) -> Result<Vec<Row>, String> {
+        let provide_start = Instant::now();
         let mut rows: Vec<Row> = vec![];
 
         for repository in &self.repos {
             let mut repo_rows =
-                select_gql_objects(repository, table.to_string(), selected_columns)?;
+                select_gql_objects(self, repository, table.to_string(), selected_columns)?;
             rows.append(&mut repo_rows);
         }
 
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.provide_duration += provide_start.elapsed();
+            match stats.table_rows.iter_mut().find(|(name, _)| name == table) {
+                Some((_, count)) => *count += rows.len(),
+                None => stats.table_rows.push((table.to_string(), rows.len())),
+            }
+        }
+
         Ok(rows)
     }
 }
 
 fn select_gql_objects(
+    provider: &GitDataProvider,
     repo: &gix::Repository,
     table: String,
     selected_columns: &[String],
 ) -> Result<Vec<Row>, String> {
+    let signals = &provider.signals;
     match table.as_str() {
-        "refs" => select_references(repo, selected_columns),
-        "commits" => select_commits(repo, selected_columns),
-        "branches" => select_branches(repo, selected_columns),
-        "diffs" => select_diffs(repo, selected_columns),
-        "tags" => select_tags(repo, selected_columns),
+        "refs" => select_references(repo, selected_columns, signals),
+        "commits" => select_commits(repo, selected_columns, &provider.walk, signals),
+        "branches" => select_branches(repo, selected_columns, signals),
+        "diffs" => select_diffs(repo, selected_columns, &provider.walk, signals),
+        "diff_changes" => select_diff_changes(repo, selected_columns, signals),
+        "tags" => select_tags(repo, selected_columns, signals),
+        "files" => select_files(repo, selected_columns, signals),
+        "blame" => select_blame(repo, selected_columns, &provider.blame_paths, signals),
+        "stashes" => select_stashes(repo, selected_columns, signals),
+        "remotes" => select_remotes(repo, selected_columns, signals),
+        "reflog" => select_reflog(repo, selected_columns, signals),
+        "status" => select_status(repo, selected_columns, signals),
+        "worktrees" => select_worktrees(repo, selected_columns, signals),
+        "submodules" => select_submodules(repo, selected_columns, signals),
+        "notes" => select_notes(repo, selected_columns, &provider.notes_ref, signals),
+        "commit_parents" => select_commit_parents(repo, selected_columns, signals),
+        "config" => select_config(repo, selected_columns, signals),
+        "index_entries" => select_index_entries(repo, selected_columns, signals),
+        "objects" => select_objects(repo, selected_columns, signals),
         _ => Ok(vec![Row { values: vec![] }]),
     }
 }
 
+/// The value of the `repo` column, the git dir of a repository or the checkout
+/// of a submodule, whose git dir is hidden in the superproject's `.git/modules`.
+fn repository_path(repo: &gix::Repository) -> String {
+    let path = match (repo.kind(), repo.workdir()) {
+        (gix::repository::Kind::Submodule, Some(workdir)) => workdir,
+        _ => repo.path(),
+    };
+    path.to_str().unwrap().to_string()
+}
+
+fn check_interrupted(signals: &Signals) -> Result<(), String> {
+    if signals.interrupted() {
+        return Err(INTERRUPTED_ERROR.to_string());
+    }
+    Ok(())
+}
+
 fn select_references(
     repo: &gix::Repository,
     selected_columns: &[String],
+    signals: &Signals,
 ) -> Result<Vec<Row>, String> {
     let git_references = repo.references();
     if let Err(error) = git_references {
         return Err(error.to_string());
     }
 
-    let repo_path = repo.path().to_str().unwrap().to_string();
+    let repo_path = repository_path(repo);
     let references = git_references.ok().unwrap();
     let mut rows: Vec<Row> = vec![];
 
     for reference in references.all().unwrap().flatten() {
+        check_interrupted(signals)?;
         let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
         for field_name in selected_columns {
             if field_name == "name" {
@@ -125,18 +255,19 @@ fn select_references(
     Ok(rows)
 }
 
-fn select_commits(repo: &gix::Repository, selected_columns: &[String]) -> Result<Vec<Row>, String> {
-    let head_id = repo.head_id();
-    if let Err(error) = head_id {
-        return Err(error.to_string());
-    }
-
-    let repo_path = repo.path().to_str().unwrap().to_string();
-    let revwalk = head_id.unwrap().ancestors().all().unwrap();
+fn select_commits(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    walk: &WalkScope,
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let revwalk = walk_commits(repo, walk)?;
     let mut rows: Vec<Row> = vec![];
 
-    for commit_info in revwalk {
-        let commit_info = commit_info.unwrap();
+    for commit in revwalk {
+        check_interrupted(signals)?;
+        let (commit_info, ref_name) = commit?;
         let commit = repo.find_object(commit_info.id).unwrap().into_commit();
         let commit = commit.decode().unwrap();
 
@@ -199,6 +330,13 @@ fn select_commits(repo: &gix::Repository, selected_columns: &[String]) -> Result
                 continue;
             }
 
+            if column_name == "ref_name" {
+                values.push(Box::new(TextValue {
+                    value: ref_name.to_string(),
+                }));
+                continue;
+            }
+
             if column_name == "parents_count" {
                 values.push(Box::new(IntValue {
                     value: commit.parents.len() as i64,
@@ -226,29 +364,66 @@ fn select_commits(repo: &gix::Repository, selected_columns: &[String]) -> Result
 fn select_branches(
     repo: &gix::Repository,
     selected_columns: &[String],
+    signals: &Signals,
 ) -> Result<Vec<Row>, String> {
     let mut rows: Vec<Row> = vec![];
 
-    let repo_path = repo.path().to_str().unwrap().to_string();
-    let platform = repo.references().unwrap();
-    let local_branches = platform.local_branches().unwrap();
-    let remote_branches = platform.remote_branches().unwrap();
+    let repo_path = repository_path(repo);
+    let platform = repo.references().map_err(|error| error.to_string())?;
+    let local_branches = platform
+        .local_branches()
+        .map_err(|error| error.to_string())?;
+    let remote_branches = platform
+        .remote_branches()
+        .map_err(|error| error.to_string())?;
     let local_and_remote_branches = local_branches.chain(remote_branches);
     let head_ref_result = repo.head_ref();
     if let Err(error) = head_ref_result {
         return Err(error.to_string());
     }
 
+    // A detached or unborn HEAD has no branch, so no branch is the head one
     let head_ref_option = head_ref_result.unwrap();
-    if head_ref_option.is_none() {
-        return Ok(rows);
-    }
 
-    let head_ref = head_ref_option.unwrap();
+    let select_tracking = selected_columns.contains(&"ahead".to_string())
+        || selected_columns.contains(&"behind".to_string());
+    let head_ancestors = if selected_columns.contains(&"merged_into_head".to_string()) {
+        match repo.head_id() {
+            Ok(head_id) => ancestor_ids(repo, head_id.detach(), signals)?,
+            Err(_) => HashSet::new(),
+        }
+    } else {
+        HashSet::new()
+    };
 
     for mut branch in local_and_remote_branches.flatten() {
+        check_interrupted(signals)?;
         let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
 
+        let tip_id = branch.clone().peel_to_id().ok().map(|id| id.detach());
+
+        let upstream = repo
+            .branch_remote_tracking_ref_name(branch.name(), gix::remote::Direction::Fetch)
+            .and_then(|name| name.ok())
+            .map(|name| name.into_owned());
+
+        // Commits only reachable from the branch and only reachable from its upstream
+        let (mut ahead, mut behind) = (None, None);
+        if select_tracking {
+            let upstream_id = upstream.as_ref().and_then(|name| {
+                repo.try_find_reference(name.as_bstr())
+                    .ok()
+                    .flatten()
+                    .and_then(|mut reference| reference.peel_to_id().ok())
+                    .map(|id| id.detach())
+            });
+
+            if let (Some(tip_id), Some(upstream_id)) = (tip_id, upstream_id) {
+                ahead = Some(count_commits_hiding(repo, tip_id, upstream_id, signals)?);
+                behind = Some(count_commits_hiding(repo, upstream_id, tip_id, signals)?);
+            }
+        }
+
         for column_name in selected_columns {
             if column_name == "name" {
                 let branch_name = branch.name().as_bstr().to_string();
@@ -256,10 +431,59 @@ fn select_branches(
                 continue;
             }
 
+            if column_name == "upstream" {
+                if let Some(upstream) = &upstream {
+                    values.push(Box::new(TextValue {
+                        value: upstream.shorten().to_string(),
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "ahead" {
+                if let Some(ahead) = ahead {
+                    values.push(Box::new(IntValue {
+                        value: ahead as i64,
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "behind" {
+                if let Some(behind) = behind {
+                    values.push(Box::new(IntValue {
+                        value: behind as i64,
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "merged_into_head" {
+                let merged_into_head = tip_id.is_some_and(|id| head_ancestors.contains(&id));
+                values.push(Box::new(BoolValue {
+                    value: merged_into_head,
+                }));
+                continue;
+            }
+
+            if column_name == "last_commit_id" {
+                if let Some(tip_id) = tip_id {
+                    values.push(Box::new(TextValue {
+                        value: tip_id.to_string(),
+                    }));
+                    continue;
+                }
+            }
+
             if column_name == "commit_count" {
                 let commit_count = if let Some(id) = branch.try_id() {
                     if let Ok(revwalk) = id.ancestors().all() {
-                        revwalk.count() as i64
+                        let mut count = 0;
+                        for _ in revwalk {
+                            check_interrupted(signals)?;
+                            count += 1;
+                        }
+                        count
                     } else {
                         -1
                     }
@@ -274,10 +498,13 @@ fn select_branches(
             }
 
             if column_name == "updated" {
-                if let Ok(top_commit_id) = branch.peel_to_id_in_place() {
-                    let revwalk = top_commit_id.ancestors().all().unwrap();
+                if let Ok(top_commit_id) = branch.peel_to_id() {
+                    let revwalk = top_commit_id
+                        .ancestors()
+                        .all()
+                        .map_err(|error| error.to_string())?;
                     if let Some(commit_info) = revwalk.into_iter().next() {
-                        let commit_info = commit_info.unwrap();
+                        let commit_info = commit_info.map_err(|error| error.to_string())?;
                         if let Some(commit_timestamp) = commit_info.commit_time {
                             values.push(Box::new(DateTimeValue {
                                 value: commit_timestamp,
@@ -298,9 +525,10 @@ fn select_branches(
             }
 
             if column_name == "is_head" {
-                values.push(Box::new(BoolValue {
-                    value: branch.inner == head_ref.inner,
-                }));
+                let is_head = head_ref_option
+                    .as_ref()
+                    .is_some_and(|head_ref| branch.inner == head_ref.inner);
+                values.push(Box::new(BoolValue { value: is_head }));
                 continue;
             }
 
@@ -330,15 +558,212 @@ fn select_branches(
     Ok(rows)
 }
 
-fn select_diffs(repo: &gix::Repository, selected_columns: &[String]) -> Result<Vec<Row>, String> {
+/// The commits reachable from the tips of `walk`, each paired with the name of
+/// the first tip it was reached from. Commits already reached from an earlier
+/// tip are pruned together with their ancestors, and the commits are walked
+/// lazily as the returned iterator is consumed.
+fn walk_commits<'repo>(
+    repo: &'repo gix::Repository,
+    walk: &WalkScope,
+) -> Result<CommitWalk<'repo>, String> {
+    let mut tips: Vec<(String, gix::ObjectId)> = vec![];
+    let mut hidden: Vec<gix::ObjectId> = vec![];
+    match walk {
+        WalkScope::Head => {
+            let head_id = repo.head_id().map_err(|error| error.to_string())?;
+            tips.push(("HEAD".to_string(), head_id.detach()));
+        }
+        WalkScope::Revspec(revspec) => {
+            // Every whitespace separated revspec adds tips or hides commits,
+            // `^v1.0 HEAD` and `v1.0..HEAD` select the same commits
+            for spec in revspec.split_whitespace() {
+                let parsed = repo.rev_parse(spec).map_err(|error| error.to_string())?;
+                // Annotated tags like `^v1.0` name the tag object, walk its commit
+                let commit_id = |id: gix::ObjectId| -> Result<gix::ObjectId, String> {
+                    let object = repo.find_object(id).map_err(|error| error.to_string())?;
+                    let commit = object.peel_to_commit().map_err(|error| error.to_string())?;
+                    Ok(commit.id)
+                };
+                match parsed.detach() {
+                    gix::revision::plumbing::Spec::Include(id) => {
+                        tips.push((spec.to_string(), commit_id(id)?));
+                    }
+                    gix::revision::plumbing::Spec::Exclude(id) => hidden.push(commit_id(id)?),
+                    gix::revision::plumbing::Spec::Range { from, to } => {
+                        tips.push((spec.to_string(), commit_id(to)?));
+                        hidden.push(commit_id(from)?);
+                    }
+                    gix::revision::plumbing::Spec::Merge { theirs, ours } => {
+                        let (theirs, ours) = (commit_id(theirs)?, commit_id(ours)?);
+                        tips.push((spec.to_string(), theirs));
+                        tips.push((spec.to_string(), ours));
+                        // Unrelated histories have no merge base to hide
+                        if let Ok(merge_base) = repo.merge_base(theirs, ours) {
+                            hidden.push(merge_base.detach());
+                        }
+                    }
+                    gix::revision::plumbing::Spec::IncludeOnlyParents(id) => {
+                        let commit = repo
+                            .find_commit(commit_id(id)?)
+                            .map_err(|error| error.to_string())?;
+                        for parent_id in commit.parent_ids() {
+                            tips.push((spec.to_string(), parent_id.detach()));
+                        }
+                    }
+                    gix::revision::plumbing::Spec::ExcludeParents(id) => {
+                        let id = commit_id(id)?;
+                        let commit = repo.find_commit(id).map_err(|error| error.to_string())?;
+                        hidden.extend(commit.parent_ids().map(|parent_id| parent_id.detach()));
+                        tips.push((spec.to_string(), id));
+                    }
+                }
+            }
+        }
+        WalkScope::Branches | WalkScope::All => {
+            let all_refs = matches!(walk, WalkScope::All);
+            if all_refs {
+                if let Ok(head_id) = repo.head_id() {
+                    tips.push(("HEAD".to_string(), head_id.detach()));
+                }
+            }
+
+            let platform = repo.references().map_err(|error| error.to_string())?;
+            let references = if all_refs {
+                platform.all()
+            } else {
+                platform.local_branches()
+            }
+            .map_err(|error| error.to_string())?;
+
+            for mut reference in references.flatten() {
+                let ref_name = reference.name().as_bstr().to_string();
+                // Tags may point to trees or blobs, only commits can be walked
+                let Ok(id) = reference.peel_to_id() else {
+                    continue;
+                };
+                if id
+                    .object()
+                    .is_ok_and(|object| object.kind == gix::object::Kind::Commit)
+                {
+                    tips.push((ref_name, id.detach()));
+                }
+            }
+        }
+    }
+
+    Ok(CommitWalk {
+        repo,
+        tips: tips.into_iter(),
+        hidden,
+        seen: Rc::default(),
+        current: None,
+    })
+}
+
+/// Iterator returned by `walk_commits`, walking one tip after the other.
+struct CommitWalk<'repo> {
+    repo: &'repo gix::Repository,
+    tips: std::vec::IntoIter<(String, gix::ObjectId)>,
+    hidden: Vec<gix::ObjectId>,
+    seen: Rc<RefCell<HashSet<gix::ObjectId>>>,
+    current: Option<(gix::revision::Walk<'repo>, Rc<str>)>,
+}
+
+impl<'repo> Iterator for CommitWalk<'repo> {
+    type Item = Result<(gix::revision::walk::Info<'repo>, Rc<str>), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((revwalk, ref_name)) = &mut self.current {
+                match revwalk.next() {
+                    Some(Ok(commit_info)) => {
+                        self.seen.borrow_mut().insert(commit_info.id);
+                        return Some(Ok((commit_info, ref_name.clone())));
+                    }
+                    Some(Err(error)) => return Some(Err(error.to_string())),
+                    None => self.current = None,
+                }
+            }
+
+            let (ref_name, tip) = self.tips.next()?;
+            if self.seen.borrow().contains(&tip) {
+                continue;
+            }
+
+            let walk_seen = self.seen.clone();
+            let revwalk = self
+                .repo
+                .rev_walk([tip])
+                .with_hidden(self.hidden.iter().copied())
+                .selected(move |id| !walk_seen.borrow().contains(id));
+            match revwalk {
+                Ok(revwalk) => self.current = Some((revwalk, Rc::from(ref_name))),
+                Err(error) => return Some(Err(error.to_string())),
+            }
+        }
+    }
+}
+
+/// The number of commits reachable from `id` but not from `hidden`. The walk
+/// stops where both histories meet, at their merge base, instead of visiting
+/// the complete history of either side.
+fn count_commits_hiding(
+    repo: &gix::Repository,
+    id: gix::ObjectId,
+    hidden: gix::ObjectId,
+    signals: &Signals,
+) -> Result<usize, String> {
+    let revwalk = repo
+        .rev_walk([id])
+        .with_hidden([hidden])
+        .all()
+        .map_err(|error| error.to_string())?;
+
+    let mut count = 0;
+    for commit_info in revwalk {
+        check_interrupted(signals)?;
+        commit_info.map_err(|error| error.to_string())?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Every commit reachable from `id`, including `id` itself.
+fn ancestor_ids(
+    repo: &gix::Repository,
+    id: gix::ObjectId,
+    signals: &Signals,
+) -> Result<HashSet<gix::ObjectId>, String> {
+    let revwalk = repo
+        .rev_walk([id])
+        .all()
+        .map_err(|error| error.to_string())?;
+
+    let mut ids: HashSet<gix::ObjectId> = HashSet::new();
+    for commit_info in revwalk {
+        check_interrupted(signals)?;
+        let commit_info = commit_info.map_err(|error| error.to_string())?;
+        ids.insert(commit_info.id);
+    }
+
+    Ok(ids)
+}
+
+fn select_diffs(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    walk: &WalkScope,
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
     let repo = {
         let mut repo = repo.clone();
         repo.object_cache_size_if_unset(4 * 1024 * 1024);
         repo
     };
 
-    let revwalk = repo.head_id().unwrap().ancestors().all().unwrap();
-    let repo_path = repo.path().to_str().unwrap().to_string();
+    let revwalk = walk_commits(&repo, walk)?;
+    let repo_path = repository_path(&repo);
 
     let mut rewrite_cache = repo
         .diff_resource_cache(gix::diff::blob::pipeline::Mode::ToGit, Default::default())
@@ -350,8 +775,9 @@ fn select_diffs(repo: &gix::Repository, selected_columns: &[String]) -> Result<V
     let select_insertions_or_deletions = selected_columns.contains(&"insertions".to_string())
         || selected_columns.contains(&"deletions".to_string());
 
-    for commit_info in revwalk {
-        let commit_info = commit_info.unwrap();
+    for commit in revwalk {
+        check_interrupted(signals)?;
+        let (commit_info, ref_name) = commit?;
         let commit = commit_info.id().object().unwrap().into_commit();
         let commit_ref = commit.decode().unwrap();
         let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
@@ -386,6 +812,13 @@ fn select_diffs(repo: &gix::Repository, selected_columns: &[String]) -> Result<V
                 continue;
             }
 
+            if column_name == "ref_name" {
+                values.push(Box::new(TextValue {
+                    value: ref_name.to_string(),
+                }));
+                continue;
+            }
+
             if column_name == "repo" {
                 values.push(Box::new(TextValue {
                     value: repo_path.to_string(),
@@ -460,12 +893,221 @@ fn select_diffs(repo: &gix::Repository, selected_columns: &[String]) -> Result<V
     Ok(rows)
 }
 
-fn select_tags(repo: &gix::Repository, selected_columns: &[String]) -> Result<Vec<Row>, String> {
+/// A single file changed by a commit, collected from the tree diff callback.
+struct FileChange {
+    path: String,
+    /// The source path of a rename or copy, `None` for every other change.
+    old_path: Option<String>,
+    mode: &'static str,
+    insertions: u32,
+    deletions: u32,
+    is_binary: bool,
+}
+
+fn select_diff_changes(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo = {
+        let mut repo = repo.clone();
+        repo.object_cache_size_if_unset(4 * 1024 * 1024);
+        repo
+    };
+
+    let revwalk = repo
+        .head_id()
+        .map_err(|error| error.to_string())?
+        .ancestors()
+        .all()
+        .map_err(|error| error.to_string())?;
+    let repo_path = repository_path(&repo);
+
+    let mut rewrite_cache = repo
+        .diff_resource_cache(gix::diff::blob::pipeline::Mode::ToGit, Default::default())
+        .map_err(|error| error.to_string())?;
+
+    let mut diff_cache = rewrite_cache.clone();
+    let mut rows: Vec<Row> = vec![];
+
+    let select_line_counts = selected_columns.contains(&"insertions".to_string())
+        || selected_columns.contains(&"deletions".to_string())
+        || selected_columns.contains(&"is_binary".to_string());
+
+    for commit_info in revwalk {
+        check_interrupted(signals)?;
+        let commit_info = commit_info.map_err(|error| error.to_string())?;
+        let commit = commit_info
+            .id()
+            .object()
+            .map_err(|error| error.to_string())?
+            .into_commit();
+        let commit_ref = commit.decode().map_err(|error| error.to_string())?;
+        let time_stamp = commit_info
+            .commit_time
+            .unwrap_or_else(|| commit_ref.time().seconds);
+
+        let current = commit.tree().map_err(|error| error.to_string())?;
+        let previous = match commit_info.parent_ids().next() {
+            Some(id) => id
+                .object()
+                .map_err(|error| error.to_string())?
+                .into_commit()
+                .tree()
+                .map_err(|error| error.to_string())?,
+            None => repo.empty_tree(),
+        };
+        rewrite_cache.clear_resource_cache();
+        diff_cache.clear_resource_cache();
+
+        let mut file_changes: Vec<FileChange> = vec![];
+        let mut changes = previous.changes().map_err(|error| error.to_string())?;
+        changes.options(|options| {
+            options.track_path();
+        });
+        let diff_result = changes.for_each_to_obtain_tree_with_cache(
+            &current,
+            &mut rewrite_cache,
+            |change| -> Result<_, std::convert::Infallible> {
+                if !change.entry_mode().is_no_tree() {
+                    return Ok(gix::object::tree::diff::Action::Continue);
+                }
+
+                let path = change.location().to_string();
+                let (mode, old_path) = match &change {
+                    gix::object::tree::diff::Change::Addition { .. } => ("added", None),
+                    gix::object::tree::diff::Change::Deletion { .. } => ("deleted", None),
+                    gix::object::tree::diff::Change::Modification { .. } => ("modified", None),
+                    gix::object::tree::diff::Change::Rewrite {
+                        source_location,
+                        copy,
+                        ..
+                    } => (
+                        if *copy { "copied" } else { "renamed" },
+                        Some(source_location.to_string()),
+                    ),
+                };
+
+                let (mut insertions, mut deletions, mut is_binary) = (0, 0, false);
+                if select_line_counts {
+                    if let Ok(mut platform) = change.diff(&mut diff_cache) {
+                        match platform.line_counts() {
+                            Ok(Some(counts)) => {
+                                insertions = counts.insertions;
+                                deletions = counts.removals;
+                            }
+                            Ok(None) => is_binary = true,
+                            Err(_) => {}
+                        }
+                    }
+                }
+
+                file_changes.push(FileChange {
+                    path,
+                    old_path,
+                    mode,
+                    insertions,
+                    deletions,
+                    is_binary,
+                });
+                Ok(gix::object::tree::diff::Action::Continue)
+            },
+        );
+        if let Err(error) = diff_result {
+            return Err(error.to_string());
+        }
+
+        for file_change in file_changes {
+            let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+
+            for column_name in selected_columns {
+                if column_name == "commit_id" {
+                    values.push(Box::new(TextValue {
+                        value: commit_info.id.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "path" {
+                    values.push(Box::new(TextValue {
+                        value: file_change.path.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "old_path" {
+                    match &file_change.old_path {
+                        Some(old_path) => values.push(Box::new(TextValue {
+                            value: old_path.to_string(),
+                        })),
+                        None => values.push(Box::new(NullValue)),
+                    }
+                    continue;
+                }
+
+                if column_name == "mode" {
+                    values.push(Box::new(TextValue {
+                        value: file_change.mode.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "insertions" {
+                    values.push(Box::new(IntValue {
+                        value: file_change.insertions as i64,
+                    }));
+                    continue;
+                }
+
+                if column_name == "deletions" {
+                    values.push(Box::new(IntValue {
+                        value: file_change.deletions as i64,
+                    }));
+                    continue;
+                }
+
+                if column_name == "is_binary" {
+                    values.push(Box::new(BoolValue {
+                        value: file_change.is_binary,
+                    }));
+                    continue;
+                }
+
+                if column_name == "datetime" {
+                    values.push(Box::new(DateTimeValue { value: time_stamp }));
+                    continue;
+                }
+
+                if column_name == "repo" {
+                    values.push(Box::new(TextValue {
+                        value: repo_path.to_string(),
+                    }));
+                    continue;
+                }
+
+                values.push(Box::new(NullValue));
+            }
+
+            let row = Row { values };
+            rows.push(row);
+        }
+    }
+
+    Ok(rows)
+}
+
+fn select_tags(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
     let platform = repo.references().unwrap();
     let tag_names = platform.tags().unwrap();
-    let repo_path = repo.path().to_str().unwrap().to_string();
+    let repo_path = repository_path(repo);
     let mut rows: Vec<Row> = vec![];
     for tag_ref in tag_names.flatten() {
+        check_interrupted(signals)?;
+        let tag_info = peel_tag(&tag_ref);
         let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
 
         for column_name in selected_columns {
@@ -480,19 +1122,1930 @@ fn select_tags(repo: &gix::Repository, selected_columns: &[String]) -> Result<Ve
                 continue;
             }
 
-            if column_name == "repo" {
+            if column_name == "target_id" {
                 values.push(Box::new(TextValue {
-                    value: repo_path.to_string(),
+                    value: tag_info.target_id.to_string(),
                 }));
                 continue;
             }
 
-            values.push(Box::new(NullValue));
-        }
+            if column_name == "target_type" {
+                values.push(Box::new(TextValue {
+                    value: tag_info.target_type.to_string(),
+                }));
+                continue;
+            }
 
-        let row = Row { values };
-        rows.push(row);
-    }
+            if column_name == "is_annotated" {
+                values.push(Box::new(BoolValue {
+                    value: tag_info.is_annotated,
+                }));
+                continue;
+            }
 
-    Ok(rows)
+            if column_name == "is_signed" {
+                values.push(Box::new(BoolValue {
+                    value: tag_info.is_signed,
+                }));
+                continue;
+            }
+
+            if column_name == "tagger_name" {
+                if let Some(tagger_name) = &tag_info.tagger_name {
+                    values.push(Box::new(TextValue {
+                        value: tagger_name.to_string(),
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "tagger_email" {
+                if let Some(tagger_email) = &tag_info.tagger_email {
+                    values.push(Box::new(TextValue {
+                        value: tagger_email.to_string(),
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "datetime" {
+                if let Some(time_stamp) = tag_info.datetime {
+                    values.push(Box::new(DateTimeValue { value: time_stamp }));
+                    continue;
+                }
+            }
+
+            if column_name == "message" {
+                if let Some(message) = &tag_info.message {
+                    values.push(Box::new(TextValue {
+                        value: message.to_string(),
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "commit_datetime" {
+                if let Some(time_stamp) = tag_info.commit_datetime {
+                    values.push(Box::new(DateTimeValue { value: time_stamp }));
+                    continue;
+                }
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// What a tag reference points to, tagger fields are only set for annotated tags.
+#[derive(Default)]
+struct TagInfo {
+    target_id: String,
+    target_type: String,
+    is_annotated: bool,
+    is_signed: bool,
+    tagger_name: Option<String>,
+    tagger_email: Option<String>,
+    datetime: Option<i64>,
+    message: Option<String>,
+    commit_datetime: Option<i64>,
+}
+
+fn peel_tag(tag_ref: &gix::Reference<'_>) -> TagInfo {
+    let mut tag_info = TagInfo::default();
+    let Some(object) = tag_ref.try_id().and_then(|id| id.object().ok()) else {
+        return tag_info;
+    };
+
+    if object.kind == gix::object::Kind::Tag {
+        tag_info.is_annotated = true;
+        if let Ok(tag) = object.clone().try_into_tag() {
+            if let Ok(tag_ref) = tag.decode() {
+                tag_info.target_id = tag_ref.target().to_string();
+                tag_info.target_type = tag_ref.target_kind.to_string();
+                tag_info.is_signed = tag_ref.pgp_signature.is_some();
+                tag_info.message = Some(tag_ref.message.to_string());
+                if let Some(tagger) = tag_ref.tagger {
+                    tag_info.tagger_name = Some(tagger.name.to_string());
+                    tag_info.tagger_email = Some(tagger.email.to_string());
+                    tag_info.datetime = Some(tagger.seconds());
+                }
+            }
+        }
+    } else {
+        tag_info.target_id = object.id.to_string();
+        tag_info.target_type = object.kind.to_string();
+    }
+
+    tag_info.commit_datetime = object
+        .peel_to_kind(gix::object::Kind::Commit)
+        .ok()
+        .and_then(|commit| commit.into_commit().time().ok())
+        .map(|time| time.seconds);
+
+    tag_info
+}
+
+fn select_files(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let head_commit = repo.head_commit();
+    if let Err(error) = head_commit {
+        return Err(error.to_string());
+    }
+
+    let tree = head_commit
+        .unwrap()
+        .tree()
+        .map_err(|error| error.to_string())?;
+
+    let mut recorder = gix::traverse::tree::Recorder::default();
+    tree.traverse()
+        .breadthfirst(&mut recorder)
+        .map_err(|error| error.to_string())?;
+
+    let repo_path = repository_path(repo);
+    let mut rows: Vec<Row> = vec![];
+
+    for entry in recorder.records {
+        check_interrupted(signals)?;
+        if entry.mode.is_tree() || entry.mode.is_commit() {
+            continue;
+        }
+
+        let path = entry.filepath.to_string();
+        let name = path.rsplit('/').next().unwrap_or_default().to_string();
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "path" {
+                values.push(Box::new(TextValue {
+                    value: path.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "name" {
+                values.push(Box::new(TextValue {
+                    value: name.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "extension" {
+                let extension = match name.rfind('.') {
+                    Some(index) if index > 0 => name[index + 1..].to_string(),
+                    _ => String::default(),
+                };
+                values.push(Box::new(TextValue { value: extension }));
+                continue;
+            }
+
+            if column_name == "mode" {
+                values.push(Box::new(TextValue {
+                    value: entry.mode.kind().as_octal_str().to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "size" {
+                if let Ok(header) = repo.find_header(entry.oid) {
+                    values.push(Box::new(IntValue {
+                        value: header.size() as i64,
+                    }));
+                    continue;
+                }
+
+                values.push(Box::new(NullValue));
+                continue;
+            }
+
+            if column_name == "blob_id" {
+                values.push(Box::new(TextValue {
+                    value: entry.oid.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "is_binary" {
+                if let Ok(blob) = repo.find_blob(entry.oid) {
+                    values.push(Box::new(BoolValue {
+                        value: is_binary_content(&blob.data),
+                    }));
+                    continue;
+                }
+
+                values.push(Box::new(NullValue));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// Same heuristic as git, content is binary when a NUL byte shows up in the
+/// first 8000 bytes.
+fn is_binary_content(data: &[u8]) -> bool {
+    data.iter().take(8000).any(|byte| *byte == 0)
+}
+
+/// A line of a file at HEAD and the commit that last touched it.
+struct BlameLine {
+    line_number: usize,
+    line: String,
+    commit_id: gix::ObjectId,
+}
+
+fn select_blame(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    blame_paths: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo = {
+        let mut repo = repo.clone();
+        repo.object_cache_size_if_unset(4 * 1024 * 1024);
+        repo
+    };
+
+    let head_commit = repo.head_commit();
+    if let Err(error) = head_commit {
+        return Err(error.to_string());
+    }
+
+    let head_commit = head_commit.unwrap();
+    let paths: Vec<String> = if blame_paths.is_empty() {
+        let tree = head_commit.tree().map_err(|error| error.to_string())?;
+        let mut recorder = gix::traverse::tree::Recorder::default();
+        tree.traverse()
+            .breadthfirst(&mut recorder)
+            .map_err(|error| error.to_string())?;
+        recorder
+            .records
+            .into_iter()
+            .filter(|entry| entry.mode.is_blob())
+            .map(|entry| entry.filepath.to_string())
+            .collect()
+    } else {
+        blame_paths.to_vec()
+    };
+
+    let repo_path = repository_path(&repo);
+    let mut signatures: HashMap<gix::ObjectId, (String, String, i64)> = HashMap::new();
+    let mut rows: Vec<Row> = vec![];
+
+    for path in paths {
+        check_interrupted(signals)?;
+        let blame_lines = blame_file(&repo, head_commit.id, &path)?;
+
+        for blame_line in blame_lines {
+            if let Entry::Vacant(signature) = signatures.entry(blame_line.commit_id) {
+                let commit = repo
+                    .find_commit(blame_line.commit_id)
+                    .map_err(|error| error.to_string())?;
+                let commit_ref = commit.decode().map_err(|error| error.to_string())?;
+                let author = commit_ref.author();
+                signature.insert((
+                    author.name.to_string(),
+                    author.email.to_string(),
+                    author.seconds(),
+                ));
+            }
+
+            let (author_name, author_email, time_stamp) = &signatures[&blame_line.commit_id];
+            let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+
+            for column_name in selected_columns {
+                if column_name == "path" {
+                    values.push(Box::new(TextValue {
+                        value: path.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "line_number" {
+                    values.push(Box::new(IntValue {
+                        value: blame_line.line_number as i64,
+                    }));
+                    continue;
+                }
+
+                if column_name == "line" {
+                    values.push(Box::new(TextValue {
+                        value: blame_line.line.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "commit_id" {
+                    values.push(Box::new(TextValue {
+                        value: blame_line.commit_id.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "author_name" {
+                    values.push(Box::new(TextValue {
+                        value: author_name.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "author_email" {
+                    values.push(Box::new(TextValue {
+                        value: author_email.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "datetime" {
+                    values.push(Box::new(DateTimeValue { value: *time_stamp }));
+                    continue;
+                }
+
+                if column_name == "repo" {
+                    values.push(Box::new(TextValue {
+                        value: repo_path.to_string(),
+                    }));
+                    continue;
+                }
+
+                values.push(Box::new(NullValue));
+            }
+
+            let row = Row { values };
+            rows.push(row);
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Blame `path` as it is in `tip` with gix, following renames. Each line of
+/// the file is attributed to the commit that introduced it, binary files
+/// produce no lines.
+fn blame_file(
+    repo: &gix::Repository,
+    tip: gix::ObjectId,
+    path: &str,
+) -> Result<Vec<BlameLine>, String> {
+    let entry = repo
+        .find_commit(tip)
+        .map_err(|error| error.to_string())?
+        .tree()
+        .map_err(|error| error.to_string())?
+        .lookup_entry_by_path(path)
+        .map_err(|error| error.to_string())?
+        .filter(|entry| entry.mode().is_blob());
+    let Some(entry) = entry else {
+        return Err(format!("Path '{path}' does not exist at HEAD"));
+    };
+
+    let blob = repo
+        .find_blob(entry.object_id())
+        .map_err(|error| error.to_string())?;
+    if is_binary_content(&blob.data) {
+        return Ok(vec![]);
+    }
+
+    let options = gix::blame::Options {
+        diff_algorithm: gix::diff::blob::Algorithm::Histogram,
+        range: gix::blame::BlameRanges::default(),
+        since: None,
+        rewrites: Some(gix::diff::Rewrites::default()),
+        debug_track_path: false,
+    };
+    let outcome = repo
+        .blame_file(gix::bstr::BStr::new(path), tip, options)
+        .map_err(|error| error.to_string())?;
+
+    let content = String::from_utf8_lossy(&outcome.blob).to_string();
+    let mut lines: Vec<BlameLine> = content
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| BlameLine {
+            line_number: index + 1,
+            line: line.trim_end_matches(['\r', '\n']).to_string(),
+            commit_id: tip,
+        })
+        .collect();
+
+    // The entries cover every line of the blamed file in consecutive hunks
+    for entry in &outcome.entries {
+        let start = entry.start_in_blamed_file as usize;
+        let end = start + entry.len.get() as usize;
+        for line in lines.iter_mut().take(end).skip(start) {
+            line.commit_id = entry.commit_id;
+        }
+    }
+
+    Ok(lines)
+}
+
+fn select_stashes(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let mut rows: Vec<Row> = vec![];
+
+    let stash_ref = repo
+        .try_find_reference("refs/stash")
+        .map_err(|error| error.to_string())?;
+    let Some(stash_ref) = stash_ref else {
+        return Ok(rows);
+    };
+
+    // The newest stash is the last reflog line, walk backwards so that the
+    // enumeration matches the `stash@{index}` notation.
+    let mut log_platform = stash_ref.log_iter();
+    let Some(log_lines) = log_platform.rev().map_err(|error| error.to_string())? else {
+        return Ok(rows);
+    };
+
+    for (index, log_line) in log_lines.enumerate() {
+        check_interrupted(signals)?;
+        let log_line = log_line.map_err(|error| error.to_string())?;
+        let message = log_line.message.to_string();
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "index" {
+                values.push(Box::new(IntValue {
+                    value: index as i64,
+                }));
+                continue;
+            }
+
+            if column_name == "message" {
+                values.push(Box::new(TextValue {
+                    value: message.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "commit_id" {
+                values.push(Box::new(TextValue {
+                    value: log_line.new_oid.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "branch" {
+                // Stash messages look like "WIP on main: ..." or "On main: ..."
+                let branch = message
+                    .strip_prefix("WIP on ")
+                    .or_else(|| message.strip_prefix("On "))
+                    .and_then(|rest| rest.split_once(':'))
+                    .map_or_else(String::default, |(branch, _)| branch.to_string());
+                values.push(Box::new(TextValue { value: branch }));
+                continue;
+            }
+
+            if column_name == "author_name" {
+                values.push(Box::new(TextValue {
+                    value: log_line.signature.name.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "datetime" {
+                values.push(Box::new(DateTimeValue {
+                    value: log_line.signature.time.seconds,
+                }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn select_remotes(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let mut rows: Vec<Row> = vec![];
+
+    for remote_name in repo.remote_names() {
+        check_interrupted(signals)?;
+        let remote = repo
+            .find_remote(remote_name.as_ref())
+            .map_err(|error| error.to_string())?;
+
+        let url = |direction: gix::remote::Direction| -> String {
+            remote
+                .url(direction)
+                .map_or_else(String::default, |url| url.to_bstring().to_string())
+        };
+
+        let refspecs = |direction: gix::remote::Direction| -> String {
+            remote
+                .refspecs(direction)
+                .iter()
+                .map(|refspec| refspec.to_ref().to_bstring().to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "name" {
+                values.push(Box::new(TextValue {
+                    value: remote_name.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "fetch_url" {
+                values.push(Box::new(TextValue {
+                    value: url(gix::remote::Direction::Fetch),
+                }));
+                continue;
+            }
+
+            if column_name == "push_url" {
+                values.push(Box::new(TextValue {
+                    value: url(gix::remote::Direction::Push),
+                }));
+                continue;
+            }
+
+            if column_name == "fetch_refspecs" {
+                values.push(Box::new(TextValue {
+                    value: refspecs(gix::remote::Direction::Fetch),
+                }));
+                continue;
+            }
+
+            if column_name == "push_refspecs" {
+                values.push(Box::new(TextValue {
+                    value: refspecs(gix::remote::Direction::Push),
+                }));
+                continue;
+            }
+
+            if column_name == "tag_mode" {
+                let tag_mode = match remote.fetch_tags() {
+                    gix::remote::fetch::Tags::None => "none",
+                    gix::remote::fetch::Tags::Included => "included",
+                    gix::remote::fetch::Tags::All => "all",
+                };
+                values.push(Box::new(TextValue {
+                    value: tag_mode.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn select_reflog(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let mut rows: Vec<Row> = vec![];
+
+    let mut references: Vec<gix::Reference<'_>> = vec![];
+    if let Ok(Some(head)) = repo.try_find_reference("HEAD") {
+        references.push(head);
+    }
+
+    let platform = repo.references().map_err(|error| error.to_string())?;
+    for reference in platform.all().map_err(|error| error.to_string())?.flatten() {
+        if reference.name().as_bstr() != "HEAD" {
+            references.push(reference);
+        }
+    }
+
+    for reference in references {
+        let ref_name = reference.name().as_bstr().to_string();
+        let mut log_platform = reference.log_iter();
+        let Some(log_lines) = log_platform.rev().map_err(|error| error.to_string())? else {
+            continue;
+        };
+
+        for (index, log_line) in log_lines.enumerate() {
+            check_interrupted(signals)?;
+            let log_line = log_line.map_err(|error| error.to_string())?;
+            let message = log_line.message.to_string();
+
+            let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+            for column_name in selected_columns {
+                if column_name == "ref_name" {
+                    values.push(Box::new(TextValue {
+                        value: ref_name.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "index" {
+                    values.push(Box::new(IntValue {
+                        value: index as i64,
+                    }));
+                    continue;
+                }
+
+                if column_name == "old_id" {
+                    values.push(Box::new(TextValue {
+                        value: log_line.previous_oid.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "new_id" {
+                    values.push(Box::new(TextValue {
+                        value: log_line.new_oid.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "committer_name" {
+                    values.push(Box::new(TextValue {
+                        value: log_line.signature.name.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "committer_email" {
+                    values.push(Box::new(TextValue {
+                        value: log_line.signature.email.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "datetime" {
+                    values.push(Box::new(DateTimeValue {
+                        value: log_line.signature.time.seconds,
+                    }));
+                    continue;
+                }
+
+                if column_name == "action" {
+                    values.push(Box::new(TextValue {
+                        value: reflog_action(&ref_name, &message),
+                    }));
+                    continue;
+                }
+
+                if column_name == "message" {
+                    values.push(Box::new(TextValue {
+                        value: message.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "repo" {
+                    values.push(Box::new(TextValue {
+                        value: repo_path.to_string(),
+                    }));
+                    continue;
+                }
+
+                values.push(Box::new(NullValue));
+            }
+
+            let row = Row { values };
+            rows.push(row);
+        }
+    }
+
+    Ok(rows)
+}
+
+/// The verb of a reflog message, "rebase (finish): ..." or "commit (amend): ..."
+/// become `rebase` and `commit`. Stash entries carry "WIP on main: ..." or
+/// "On main: ..." messages and are reported as `stash`.
+fn reflog_action(ref_name: &str, message: &str) -> String {
+    if ref_name == "refs/stash" {
+        return "stash".to_string();
+    }
+
+    let prefix = message
+        .split_once(':')
+        .map_or(message, |(prefix, _)| prefix);
+    prefix
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The state of a path in the index and the working tree.
+#[derive(Default)]
+struct PathStatus {
+    index_status: &'static str,
+    worktree_status: &'static str,
+    is_untracked: bool,
+    is_ignored: bool,
+    is_conflicted: bool,
+}
+
+fn select_status(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let mut statuses: BTreeMap<String, PathStatus> = BTreeMap::new();
+
+    // Index against HEAD, an unborn HEAD is compared against the empty tree
+    let head_tree = repo.head_tree().unwrap_or_else(|_| repo.empty_tree());
+    let mut recorder = gix::traverse::tree::Recorder::default();
+    head_tree
+        .traverse()
+        .breadthfirst(&mut recorder)
+        .map_err(|error| error.to_string())?;
+    let mut head_entries: HashMap<String, _> = recorder
+        .records
+        .into_iter()
+        .filter(|entry| !entry.mode.is_tree())
+        .map(|entry| (entry.filepath.to_string(), (entry.oid, entry.mode)))
+        .collect();
+
+    let index = repo.index_or_empty().map_err(|error| error.to_string())?;
+    for entry in index.entries() {
+        check_interrupted(signals)?;
+        let path = entry.path(&index).to_string();
+        let status = statuses.entry(path.to_string()).or_default();
+
+        if entry.stage_raw() != 0 {
+            status.is_conflicted = true;
+            status.index_status = "conflicted";
+            head_entries.remove(&path);
+            continue;
+        }
+
+        // A staged mode change such as `chmod +x` keeps the blob id
+        status.index_status = match head_entries.remove(&path) {
+            None => "added",
+            Some((id, mode)) if id != entry.id || Some(mode) != entry.mode.to_tree_entry_mode() => {
+                "modified"
+            }
+            Some(_) => "unmodified",
+        };
+    }
+
+    for path in head_entries.into_keys() {
+        let status = statuses.entry(path).or_default();
+        status.index_status = "deleted";
+    }
+
+    // Working tree against the index, including untracked and ignored files.
+    // Bare repositories have no working tree to compare against.
+    let worktree_iter = if repo.worktree().is_some() {
+        let iter = repo
+            .status(gix::progress::Discard)
+            .map_err(|error| error.to_string())?
+            .untracked_files(gix::status::UntrackedFiles::Files)
+            .index_worktree_rewrites(None)
+            .dirwalk_options(|options| {
+                options.emit_ignored(Some(gix::dir::walk::EmissionMode::Matching))
+            })
+            .into_index_worktree_iter(Vec::new())
+            .map_err(|error| error.to_string())?;
+        Some(iter)
+    } else {
+        None
+    };
+
+    for item in worktree_iter.into_iter().flatten() {
+        check_interrupted(signals)?;
+        let item = item.map_err(|error| error.to_string())?;
+        match item {
+            gix::status::index_worktree::Item::Modification {
+                rela_path, status, ..
+            } => {
+                let path_status = statuses.entry(rela_path.to_string()).or_default();
+                path_status.worktree_status = match status {
+                    gix::status::plumbing::index_as_worktree::EntryStatus::Conflict { .. } => {
+                        path_status.is_conflicted = true;
+                        "conflicted"
+                    }
+                    gix::status::plumbing::index_as_worktree::EntryStatus::Change(change) => {
+                        match change {
+                            gix::status::plumbing::index_as_worktree::Change::Removed => "deleted",
+                            gix::status::plumbing::index_as_worktree::Change::Type { .. } => {
+                                "type_changed"
+                            }
+                            _ => "modified",
+                        }
+                    }
+                    gix::status::plumbing::index_as_worktree::EntryStatus::IntentToAdd => {
+                        "intent_to_add"
+                    }
+                    gix::status::plumbing::index_as_worktree::EntryStatus::NeedsUpdate {
+                        ..
+                    } => "unmodified",
+                };
+            }
+            gix::status::index_worktree::Item::DirectoryContents { entry, .. }
+            | gix::status::index_worktree::Item::Rewrite {
+                dirwalk_entry: entry,
+                ..
+            } => {
+                let path_status = statuses.entry(entry.rela_path.to_string()).or_default();
+                match entry.status {
+                    gix::dir::entry::Status::Untracked => {
+                        path_status.is_untracked = true;
+                        path_status.worktree_status = "untracked";
+                    }
+                    gix::dir::entry::Status::Ignored(_) => {
+                        path_status.is_ignored = true;
+                        path_status.worktree_status = "ignored";
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let mut rows: Vec<Row> = vec![];
+    for (path, status) in statuses {
+        let index_status = if status.index_status.is_empty() {
+            "unmodified"
+        } else {
+            status.index_status
+        };
+        let worktree_status = if status.worktree_status.is_empty() {
+            "unmodified"
+        } else {
+            status.worktree_status
+        };
+
+        // Only report paths that differ somewhere, like `git status` does
+        if index_status == "unmodified" && worktree_status == "unmodified" {
+            continue;
+        }
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "path" {
+                values.push(Box::new(TextValue {
+                    value: path.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "index_status" {
+                values.push(Box::new(TextValue {
+                    value: index_status.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "worktree_status" {
+                values.push(Box::new(TextValue {
+                    value: worktree_status.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "is_untracked" {
+                values.push(Box::new(BoolValue {
+                    value: status.is_untracked,
+                }));
+                continue;
+            }
+
+            if column_name == "is_ignored" {
+                values.push(Box::new(BoolValue {
+                    value: status.is_ignored,
+                }));
+                continue;
+            }
+
+            if column_name == "is_conflicted" {
+                values.push(Box::new(BoolValue {
+                    value: status.is_conflicted,
+                }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// A checkout of the repository, either the main worktree or a linked one.
+struct WorktreeInfo {
+    path: String,
+    branch: String,
+    head_id: String,
+    is_locked: bool,
+    lock_reason: String,
+    is_prunable: bool,
+    is_main: bool,
+}
+
+fn select_worktrees(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+
+    // Linked worktrees are only listed by the main repository, which makes the
+    // table identical no matter which checkout the plugin was started from.
+    let main_repo = repo.main_repo().map_err(|error| error.to_string())?;
+
+    let head_info = |repo: &gix::Repository| -> (String, String) {
+        let branch = repo
+            .head_name()
+            .ok()
+            .flatten()
+            .map_or_else(String::default, |name| name.shorten().to_string());
+        let head_id = repo
+            .head_id()
+            .map_or_else(|_| String::default(), |id| id.to_string());
+        (branch, head_id)
+    };
+
+    let mut worktrees: Vec<WorktreeInfo> = vec![];
+    if let Some(worktree) = main_repo.worktree() {
+        let (branch, head_id) = head_info(&main_repo);
+        worktrees.push(WorktreeInfo {
+            path: worktree.base().to_string_lossy().to_string(),
+            branch,
+            head_id,
+            is_locked: false,
+            lock_reason: String::default(),
+            is_prunable: false,
+            is_main: true,
+        });
+    }
+
+    for proxy in main_repo.worktrees().map_err(|error| error.to_string())? {
+        check_interrupted(signals)?;
+        let base = proxy.base().ok();
+        let is_locked = proxy.is_locked();
+        let lock_reason = proxy
+            .lock_reason()
+            .map_or_else(String::default, |reason| reason.to_string());
+        let is_prunable = !is_locked && !base.as_ref().is_some_and(|base| base.exists());
+        let (branch, head_id) = proxy
+            .into_repo_with_possibly_inaccessible_worktree()
+            .map_or_else(
+                |_| (String::default(), String::default()),
+                |repo| head_info(&repo),
+            );
+
+        worktrees.push(WorktreeInfo {
+            path: base.map_or_else(String::default, |base| base.to_string_lossy().to_string()),
+            branch,
+            head_id,
+            is_locked,
+            lock_reason,
+            is_prunable,
+            is_main: false,
+        });
+    }
+
+    let mut rows: Vec<Row> = vec![];
+    for worktree in worktrees {
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "path" {
+                values.push(Box::new(TextValue {
+                    value: worktree.path.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "branch" {
+                values.push(Box::new(TextValue {
+                    value: worktree.branch.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "head_id" {
+                values.push(Box::new(TextValue {
+                    value: worktree.head_id.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "is_locked" {
+                values.push(Box::new(BoolValue {
+                    value: worktree.is_locked,
+                }));
+                continue;
+            }
+
+            if column_name == "lock_reason" {
+                values.push(Box::new(TextValue {
+                    value: worktree.lock_reason.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "is_prunable" {
+                values.push(Box::new(BoolValue {
+                    value: worktree.is_prunable,
+                }));
+                continue;
+            }
+
+            if column_name == "is_main" {
+                values.push(Box::new(BoolValue {
+                    value: worktree.is_main,
+                }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn select_submodules(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let mut rows: Vec<Row> = vec![];
+
+    let submodules = repo.submodules().map_err(|error| error.to_string())?;
+    let Some(submodules) = submodules else {
+        return Ok(rows);
+    };
+
+    for submodule in submodules {
+        check_interrupted(signals)?;
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "name" {
+                values.push(Box::new(TextValue {
+                    value: submodule.name().to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "path" {
+                let path = submodule
+                    .path()
+                    .map_or_else(|_| String::default(), |path| path.to_string());
+                values.push(Box::new(TextValue { value: path }));
+                continue;
+            }
+
+            if column_name == "url" {
+                let url = submodule
+                    .url()
+                    .map_or_else(|_| String::default(), |url| url.to_bstring().to_string());
+                values.push(Box::new(TextValue { value: url }));
+                continue;
+            }
+
+            if column_name == "branch" {
+                let branch = match submodule.branch() {
+                    Ok(Some(gix::submodule::config::Branch::Name(name))) => name.to_string(),
+                    Ok(Some(gix::submodule::config::Branch::CurrentInSuperproject)) => {
+                        ".".to_string()
+                    }
+                    _ => String::default(),
+                };
+                values.push(Box::new(TextValue { value: branch }));
+                continue;
+            }
+
+            if column_name == "recorded_commit_id" {
+                let recorded_id = submodule
+                    .index_id()
+                    .ok()
+                    .flatten()
+                    .or_else(|| submodule.head_id().ok().flatten());
+                values.push(Box::new(TextValue {
+                    value: recorded_id.map_or_else(String::default, |id| id.to_string()),
+                }));
+                continue;
+            }
+
+            if column_name == "checked_out_commit_id" {
+                let checked_out_id = submodule
+                    .open()
+                    .ok()
+                    .flatten()
+                    .and_then(|repo| repo.head_id().ok().map(|id| id.detach()));
+                values.push(Box::new(TextValue {
+                    value: checked_out_id.map_or_else(String::default, |id| id.to_string()),
+                }));
+                continue;
+            }
+
+            if column_name == "is_initialized" {
+                let is_initialized = submodule
+                    .state()
+                    .is_ok_and(|state| state.repository_exists && state.superproject_configuration);
+                values.push(Box::new(BoolValue {
+                    value: is_initialized,
+                }));
+                continue;
+            }
+
+            if column_name == "is_dirty" {
+                let is_dirty = submodule
+                    .status(gix::submodule::config::Ignore::None, true)
+                    .ok()
+                    .and_then(|status| status.is_dirty())
+                    .unwrap_or(false);
+                values.push(Box::new(BoolValue { value: is_dirty }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn select_notes(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    notes_ref: &str,
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let mut rows: Vec<Row> = vec![];
+
+    let reference = repo
+        .try_find_reference(notes_ref)
+        .map_err(|error| error.to_string())?;
+    let Some(mut reference) = reference else {
+        return Ok(rows);
+    };
+
+    let notes_ref = reference.name().as_bstr().to_string();
+    let tree = reference
+        .peel_to_id()
+        .map_err(|error| error.to_string())?
+        .object()
+        .map_err(|error| error.to_string())?
+        .peel_to_tree()
+        .map_err(|error| error.to_string())?;
+
+    let mut recorder = gix::traverse::tree::Recorder::default();
+    tree.traverse()
+        .breadthfirst(&mut recorder)
+        .map_err(|error| error.to_string())?;
+
+    let hex_len = repo.object_hash().len_in_hex();
+    for entry in recorder.records {
+        check_interrupted(signals)?;
+        if !entry.mode.is_blob() {
+            continue;
+        }
+
+        // Notes trees may fan out the annotated commit id into directories
+        let commit_id: String = entry.filepath.to_string().replace('/', "");
+        if commit_id.len() != hex_len || !commit_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            continue;
+        }
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "notes_ref" {
+                values.push(Box::new(TextValue {
+                    value: notes_ref.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "commit_id" {
+                values.push(Box::new(TextValue {
+                    value: commit_id.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "note" {
+                if let Ok(blob) = repo.find_blob(entry.oid) {
+                    values.push(Box::new(TextValue {
+                        value: String::from_utf8_lossy(&blob.data).to_string(),
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "note_blob_id" {
+                values.push(Box::new(TextValue {
+                    value: entry.oid.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn select_commit_parents(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let revwalk = repo
+        .head_id()
+        .map_err(|error| error.to_string())?
+        .ancestors()
+        .all()
+        .map_err(|error| error.to_string())?;
+    let mut rows: Vec<Row> = vec![];
+
+    for commit_info in revwalk {
+        check_interrupted(signals)?;
+        let commit_info = commit_info.map_err(|error| error.to_string())?;
+
+        for (parent_index, parent_id) in commit_info.parent_ids().enumerate() {
+            let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+            for column_name in selected_columns {
+                if column_name == "commit_id" {
+                    values.push(Box::new(TextValue {
+                        value: commit_info.id.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "parent_id" {
+                    values.push(Box::new(TextValue {
+                        value: parent_id.to_string(),
+                    }));
+                    continue;
+                }
+
+                if column_name == "parent_index" {
+                    values.push(Box::new(IntValue {
+                        value: parent_index as i64,
+                    }));
+                    continue;
+                }
+
+                if column_name == "repo" {
+                    values.push(Box::new(TextValue {
+                        value: repo_path.to_string(),
+                    }));
+                    continue;
+                }
+
+                values.push(Box::new(NullValue));
+            }
+
+            let row = Row { values };
+            rows.push(row);
+        }
+    }
+
+    Ok(rows)
+}
+
+fn select_config(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let mut rows: Vec<Row> = vec![];
+
+    let snapshot = repo.config_snapshot();
+    for section in snapshot.plumbing().sections() {
+        check_interrupted(signals)?;
+        let header = section.header();
+        let section_name = header.name().to_string();
+        let subsection = header
+            .subsection_name()
+            .map_or_else(String::default, |name| name.to_string());
+
+        let meta = section.meta();
+        let scope = match meta.source {
+            gix::config::Source::GitInstallation | gix::config::Source::System => "system",
+            gix::config::Source::Git | gix::config::Source::User => "global",
+            gix::config::Source::Local => "local",
+            gix::config::Source::Worktree => "worktree",
+            _ => "env",
+        };
+        let origin_path = meta
+            .path
+            .as_ref()
+            .map_or_else(String::default, |path| path.to_string_lossy().to_string());
+
+        // Multi-valued keys are listed once per value
+        let mut key_names: Vec<String> = vec![];
+        for value_name in section.value_names() {
+            let key_name = value_name.to_string();
+            if !key_names.contains(&key_name) {
+                key_names.push(key_name);
+            }
+        }
+
+        for key_name in key_names {
+            for value in section.values(&key_name) {
+                let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+                for column_name in selected_columns {
+                    if column_name == "section" {
+                        values.push(Box::new(TextValue {
+                            value: section_name.to_string(),
+                        }));
+                        continue;
+                    }
+
+                    if column_name == "subsection" {
+                        values.push(Box::new(TextValue {
+                            value: subsection.to_string(),
+                        }));
+                        continue;
+                    }
+
+                    if column_name == "key" {
+                        values.push(Box::new(TextValue {
+                            value: key_name.to_string(),
+                        }));
+                        continue;
+                    }
+
+                    if column_name == "value" {
+                        values.push(Box::new(TextValue {
+                            value: value.to_string(),
+                        }));
+                        continue;
+                    }
+
+                    if column_name == "scope" {
+                        values.push(Box::new(TextValue {
+                            value: scope.to_string(),
+                        }));
+                        continue;
+                    }
+
+                    if column_name == "origin_path" {
+                        values.push(Box::new(TextValue {
+                            value: origin_path.to_string(),
+                        }));
+                        continue;
+                    }
+
+                    if column_name == "repo" {
+                        values.push(Box::new(TextValue {
+                            value: repo_path.to_string(),
+                        }));
+                        continue;
+                    }
+
+                    values.push(Box::new(NullValue));
+                }
+
+                let row = Row { values };
+                rows.push(row);
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+fn select_index_entries(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let index = repo.index_or_empty().map_err(|error| error.to_string())?;
+    let mut rows: Vec<Row> = vec![];
+
+    for entry in index.entries() {
+        check_interrupted(signals)?;
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "path" {
+                values.push(Box::new(TextValue {
+                    value: entry.path(&index).to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "blob_id" {
+                values.push(Box::new(TextValue {
+                    value: entry.id.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "mode" {
+                values.push(Box::new(TextValue {
+                    value: format!("{:06o}", entry.mode.bits()),
+                }));
+                continue;
+            }
+
+            if column_name == "stage" {
+                values.push(Box::new(IntValue {
+                    value: entry.stage_raw() as i64,
+                }));
+                continue;
+            }
+
+            if column_name == "size" {
+                values.push(Box::new(IntValue {
+                    value: entry.stat.size as i64,
+                }));
+                continue;
+            }
+
+            if column_name == "mtime" {
+                values.push(Box::new(DateTimeValue {
+                    value: entry.stat.mtime.secs as i64,
+                }));
+                continue;
+            }
+
+            if column_name == "flags" {
+                let mut flags: Vec<&str> = vec![];
+                if entry.flags.contains(gix::index::entry::Flags::ASSUME_VALID) {
+                    flags.push("assume-valid");
+                }
+                if entry
+                    .flags
+                    .contains(gix::index::entry::Flags::SKIP_WORKTREE)
+                {
+                    flags.push("skip-worktree");
+                }
+                if entry
+                    .flags
+                    .contains(gix::index::entry::Flags::INTENT_TO_ADD)
+                {
+                    flags.push("intent-to-add");
+                }
+                values.push(Box::new(TextValue {
+                    value: flags.join(","),
+                }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// Every object of the object database as gix sees it, the repository's own
+/// objects directory followed by its alternates. An object that is stored both
+/// loose and packed, or in several packs outside of a multi-pack-index, gets
+/// one row per copy.
+fn select_objects(
+    repo: &gix::Repository,
+    selected_columns: &[String],
+    signals: &Signals,
+) -> Result<Vec<Row>, String> {
+    let repo_path = repository_path(repo);
+    let object_hash = repo.object_hash();
+    let structure = repo
+        .objects
+        .store_ref()
+        .structure()
+        .map_err(|error| error.to_string())?;
+
+    // Pair every object id with the pack it is stored in, loose objects have none
+    let mut objects: Vec<(gix::ObjectId, Option<String>)> = vec![];
+    for record in structure {
+        match record {
+            gix::odb::store::structure::Record::LooseObjectDatabase {
+                objects_directory, ..
+            } => {
+                let loose_store = gix::odb::loose::Store::at(objects_directory, object_hash);
+                for object_id in loose_store.iter() {
+                    check_interrupted(signals)?;
+                    let object_id = object_id.map_err(|error| error.to_string())?;
+                    objects.push((object_id, None));
+                }
+            }
+            gix::odb::store::structure::Record::Index { path, .. } => {
+                let pack_file = pack_file_name(&path);
+                let index = gix::odb::pack::index::File::at(&path, object_hash)
+                    .map_err(|error| error.to_string())?;
+                for entry in index.iter() {
+                    check_interrupted(signals)?;
+                    objects.push((entry.oid, Some(pack_file.to_string())));
+                }
+            }
+            gix::odb::store::structure::Record::MultiIndex { path, .. } => {
+                let multi_index = gix::odb::pack::multi_index::File::at(&path)
+                    .map_err(|error| error.to_string())?;
+                let pack_files: Vec<String> = multi_index
+                    .index_names()
+                    .iter()
+                    .map(|index_name| pack_file_name(index_name))
+                    .collect();
+                for entry in multi_index.iter() {
+                    check_interrupted(signals)?;
+                    let pack_file = pack_files.get(entry.pack_index as usize).cloned();
+                    objects.push((entry.oid, pack_file));
+                }
+            }
+            gix::odb::store::structure::Record::Empty => {}
+        }
+    }
+
+    let select_header = selected_columns.contains(&"kind".to_string())
+        || selected_columns.contains(&"size".to_string());
+
+    let mut rows: Vec<Row> = vec![];
+    for (object_id, pack_file) in objects {
+        check_interrupted(signals)?;
+        let header = if select_header {
+            repo.find_header(object_id).ok()
+        } else {
+            None
+        };
+
+        let mut values: Vec<Box<dyn Value>> = Vec::with_capacity(selected_columns.len());
+        for column_name in selected_columns {
+            if column_name == "object_id" {
+                values.push(Box::new(TextValue {
+                    value: object_id.to_string(),
+                }));
+                continue;
+            }
+
+            if column_name == "kind" {
+                if let Some(header) = &header {
+                    values.push(Box::new(TextValue {
+                        value: header.kind().to_string(),
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "size" {
+                if let Some(header) = &header {
+                    values.push(Box::new(IntValue {
+                        value: header.size() as i64,
+                    }));
+                    continue;
+                }
+            }
+
+            if column_name == "is_packed" {
+                values.push(Box::new(BoolValue {
+                    value: pack_file.is_some(),
+                }));
+                continue;
+            }
+
+            if column_name == "pack_file" {
+                values.push(Box::new(TextValue {
+                    value: pack_file.clone().unwrap_or_default(),
+                }));
+                continue;
+            }
+
+            if column_name == "repo" {
+                values.push(Box::new(TextValue {
+                    value: repo_path.to_string(),
+                }));
+                continue;
+            }
+
+            values.push(Box::new(NullValue));
+        }
+
+        let row = Row { values };
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// The file name of the pack that belongs to a pack index.
+fn pack_file_name(index_path: &std::path::Path) -> String {
+    index_path
+        .with_extension("pack")
+        .file_name()
+        .map_or_else(String::default, |name| name.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blame_file, count_commits_hiding, reflog_action, walk_commits, WalkScope};
+    use nu_protocol::Signals;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    /// A throwaway repository under the temp dir, removed when dropped.
+    struct Fixture {
+        path: PathBuf,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("nu_plugin_gitql_{name}_{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            let fixture = Fixture { path };
+            fixture.git(&["init", "--quiet", "--initial-branch=main"]);
+            fixture
+        }
+
+        fn git(&self, args: &[&str]) -> String {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(&self.path)
+                .env("GIT_CONFIG_NOSYSTEM", "1")
+                .env("GIT_CONFIG_GLOBAL", "/dev/null")
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(
+                output.status.success(),
+                "git {args:?} failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        }
+
+        fn commit(&self, file: &str, content: &str) -> gix::ObjectId {
+            std::fs::write(self.path.join(file), content).unwrap();
+            self.git(&["add", file]);
+            self.git(&["commit", "--quiet", "-m", &format!("update {file}")]);
+            gix::ObjectId::from_hex(self.git(&["rev-parse", "HEAD"]).as_bytes()).unwrap()
+        }
+
+        fn blame(&self, tip: gix::ObjectId, file: &str) -> Vec<(String, gix::ObjectId)> {
+            let repo = gix::open(&self.path).unwrap();
+            blame_file(&repo, tip, file)
+                .unwrap()
+                .into_iter()
+                .map(|line| (line.line, line.commit_id))
+                .collect()
+        }
+
+        fn walk(&self, walk: &str) -> Vec<(gix::ObjectId, String)> {
+            let repo = gix::open(&self.path).unwrap();
+            walk_commits(&repo, &WalkScope::from_flag(walk))
+                .unwrap()
+                .map(|commit| {
+                    let (commit_info, ref_name) = commit.unwrap();
+                    (commit_info.id, ref_name.to_string())
+                })
+                .collect()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn blame_file_untouched_since_root_commit() {
+        let fixture = Fixture::new("blame_untouched");
+        let root = fixture.commit("a.txt", "one\ntwo\n");
+        let head = fixture.commit("b.txt", "other\n");
+
+        assert_eq!(
+            fixture.blame(head, "a.txt"),
+            vec![("one".to_string(), root), ("two".to_string(), root)]
+        );
+    }
+
+    #[test]
+    fn blame_file_modified_line() {
+        let fixture = Fixture::new("blame_modified");
+        let root = fixture.commit("a.txt", "one\ntwo\nthree\n");
+        let head = fixture.commit("a.txt", "one\nTWO\nthree\n");
+
+        assert_eq!(
+            fixture.blame(head, "a.txt"),
+            vec![
+                ("one".to_string(), root),
+                ("TWO".to_string(), head),
+                ("three".to_string(), root),
+            ]
+        );
+    }
+
+    #[test]
+    fn blame_file_deleted_hunk() {
+        let fixture = Fixture::new("blame_deleted");
+        let root = fixture.commit("a.txt", "one\ntwo\nthree\nfour\n");
+        let middle = fixture.commit("a.txt", "one\ntwo\nthree\nfour\nfive\n");
+        let head = fixture.commit("a.txt", "one\nfour\nfive\n");
+
+        assert_eq!(
+            fixture.blame(head, "a.txt"),
+            vec![
+                ("one".to_string(), root),
+                ("four".to_string(), root),
+                ("five".to_string(), middle),
+            ]
+        );
+    }
+
+    #[test]
+    fn reflog_action_stash_entries() {
+        assert_eq!(reflog_action("HEAD", "commit (amend): fix typo"), "commit");
+        assert_eq!(
+            reflog_action("refs/stash", "WIP on main: 1a2b3c4 update a.txt"),
+            "stash"
+        );
+        assert_eq!(reflog_action("refs/stash", "On main: half done"), "stash");
+    }
+
+    #[test]
+    fn count_commits_hiding_diverged_upstream() {
+        let fixture = Fixture::new("ahead_behind");
+        fixture.commit("a.txt", "one\n");
+        fixture.commit("a.txt", "two\n");
+        fixture.git(&["branch", "upstream"]);
+        fixture.commit("b.txt", "local one\n");
+        let branch = fixture.commit("b.txt", "local two\n");
+        fixture.git(&["checkout", "--quiet", "upstream"]);
+        fixture.commit("c.txt", "remote one\n");
+        fixture.commit("c.txt", "remote two\n");
+        let upstream = fixture.commit("c.txt", "remote three\n");
+
+        let repo = gix::open(&fixture.path).unwrap();
+        let signals = Signals::empty();
+        let ahead = count_commits_hiding(&repo, branch, upstream, &signals).unwrap();
+        let behind = count_commits_hiding(&repo, upstream, branch, &signals).unwrap();
+        assert_eq!((ahead, behind), (2, 3));
+    }
+
+    #[test]
+    fn walk_all_visits_overlapping_branches_once() {
+        let fixture = Fixture::new("walk_all");
+        let root = fixture.commit("a.txt", "one\n");
+        let main = fixture.commit("a.txt", "two\n");
+        fixture.git(&["checkout", "--quiet", "-b", "feature"]);
+        let feature_one = fixture.commit("b.txt", "one\n");
+        let feature_two = fixture.commit("b.txt", "two\n");
+        fixture.git(&["checkout", "--quiet", "main"]);
+
+        assert_eq!(
+            fixture.walk("all"),
+            vec![
+                (main, "HEAD".to_string()),
+                (root, "HEAD".to_string()),
+                (feature_two, "refs/heads/feature".to_string()),
+                (feature_one, "refs/heads/feature".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn walk_revspec_range() {
+        let fixture = Fixture::new("walk_range");
+        fixture.commit("a.txt", "one\n");
+        fixture.commit("a.txt", "two\n");
+        fixture.git(&["checkout", "--quiet", "-b", "feature"]);
+        let feature_one = fixture.commit("b.txt", "one\n");
+        let feature_two = fixture.commit("b.txt", "two\n");
+        fixture.git(&["checkout", "--quiet", "main"]);
+        fixture.commit("a.txt", "three\n");
+
+        assert_eq!(
+            fixture.walk("main..feature"),
+            vec![
+                (feature_two, "main..feature".to_string()),
+                (feature_one, "main..feature".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn walk_revspec_excluding_tag() {
+        let fixture = Fixture::new("walk_exclude");
+        fixture.commit("a.txt", "one\n");
+        fixture.git(&["tag", "--annotate", "--message", "first release", "v1"]);
+        let second = fixture.commit("a.txt", "two\n");
+        let third = fixture.commit("a.txt", "three\n");
+
+        assert_eq!(
+            fixture.walk("^v1 HEAD"),
+            vec![(third, "HEAD".to_string()), (second, "HEAD".to_string())]
+        );
+    }
 }
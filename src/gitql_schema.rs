@@ -29,6 +29,71 @@ pub fn tables_fields_types() -> HashMap<&'static str, Box<dyn DataType>> {
     map.insert("commit_count", Box::new(IntType));
     map.insert("parents_count", Box::new(IntType));
     map.insert("updated", Box::new(DateType));
+    map.insert("path", Box::new(TextType));
+    map.insert("old_path", Box::new(TextType));
+    map.insert("line_number", Box::new(IntType));
+    map.insert("line", Box::new(TextType));
+    map.insert("index", Box::new(IntType));
+    map.insert("branch", Box::new(TextType));
+    map.insert("fetch_url", Box::new(TextType));
+    map.insert("push_url", Box::new(TextType));
+    map.insert("fetch_refspecs", Box::new(TextType));
+    map.insert("push_refspecs", Box::new(TextType));
+    map.insert("tag_mode", Box::new(TextType));
+    map.insert("ref_name", Box::new(TextType));
+    map.insert("old_id", Box::new(TextType));
+    map.insert("new_id", Box::new(TextType));
+    map.insert("action", Box::new(TextType));
+    map.insert("index_status", Box::new(TextType));
+    map.insert("worktree_status", Box::new(TextType));
+    map.insert("is_untracked", Box::new(BoolType));
+    map.insert("is_ignored", Box::new(BoolType));
+    map.insert("is_conflicted", Box::new(BoolType));
+    map.insert("head_id", Box::new(TextType));
+    map.insert("is_locked", Box::new(BoolType));
+    map.insert("lock_reason", Box::new(TextType));
+    map.insert("is_prunable", Box::new(BoolType));
+    map.insert("is_main", Box::new(BoolType));
+    map.insert("url", Box::new(TextType));
+    map.insert("recorded_commit_id", Box::new(TextType));
+    map.insert("checked_out_commit_id", Box::new(TextType));
+    map.insert("is_initialized", Box::new(BoolType));
+    map.insert("is_dirty", Box::new(BoolType));
+    map.insert("target_id", Box::new(TextType));
+    map.insert("target_type", Box::new(TextType));
+    map.insert("is_annotated", Box::new(BoolType));
+    map.insert("tagger_name", Box::new(TextType));
+    map.insert("tagger_email", Box::new(TextType));
+    map.insert("is_signed", Box::new(BoolType));
+    map.insert("commit_datetime", Box::new(DateType));
+    map.insert("notes_ref", Box::new(TextType));
+    map.insert("note", Box::new(TextType));
+    map.insert("note_blob_id", Box::new(TextType));
+    map.insert("parent_id", Box::new(TextType));
+    map.insert("parent_index", Box::new(IntType));
+    map.insert("section", Box::new(TextType));
+    map.insert("subsection", Box::new(TextType));
+    map.insert("key", Box::new(TextType));
+    map.insert("value", Box::new(TextType));
+    map.insert("scope", Box::new(TextType));
+    map.insert("origin_path", Box::new(TextType));
+    map.insert("stage", Box::new(IntType));
+    map.insert("mtime", Box::new(DateType));
+    map.insert("flags", Box::new(TextType));
+    map.insert("object_id", Box::new(TextType));
+    map.insert("kind", Box::new(TextType));
+    map.insert("is_packed", Box::new(BoolType));
+    map.insert("pack_file", Box::new(TextType));
+    map.insert("upstream", Box::new(TextType));
+    map.insert("ahead", Box::new(IntType));
+    map.insert("behind", Box::new(IntType));
+    map.insert("merged_into_head", Box::new(BoolType));
+    map.insert("last_commit_id", Box::new(TextType));
+    map.insert("extension", Box::new(TextType));
+    map.insert("mode", Box::new(TextType));
+    map.insert("size", Box::new(IntType));
+    map.insert("blob_id", Box::new(TextType));
+    map.insert("is_binary", Box::new(BoolType));
     map.insert("repo", Box::new(TextType));
     map
 }
@@ -50,6 +115,7 @@ pub fn tables_fields_names() -> &'static HashMap<&'static str, Vec<&'static str>
                 "committer_email",
                 "datetime",
                 "parents_count",
+                "ref_name",
                 "repo",
             ],
         );
@@ -61,6 +127,11 @@ pub fn tables_fields_names() -> &'static HashMap<&'static str, Vec<&'static str>
                 "is_head",
                 "is_remote",
                 "updated",
+                "upstream",
+                "ahead",
+                "behind",
+                "merged_into_head",
+                "last_commit_id",
                 "repo",
             ],
         );
@@ -74,10 +145,181 @@ pub fn tables_fields_names() -> &'static HashMap<&'static str, Vec<&'static str>
                 "deletions",
                 "files_changed",
                 "datetime",
+                "ref_name",
+                "repo",
+            ],
+        );
+        map.insert(
+            "tags",
+            vec![
+                "name",
+                "target_id",
+                "target_type",
+                "is_annotated",
+                "tagger_name",
+                "tagger_email",
+                "datetime",
+                "message",
+                "is_signed",
+                "commit_datetime",
+                "repo",
+            ],
+        );
+        map.insert(
+            "diff_changes",
+            vec![
+                "commit_id",
+                "path",
+                "old_path",
+                "mode",
+                "insertions",
+                "deletions",
+                "is_binary",
+                "datetime",
+                "repo",
+            ],
+        );
+        map.insert(
+            "blame",
+            vec![
+                "path",
+                "line_number",
+                "line",
+                "commit_id",
+                "author_name",
+                "author_email",
+                "datetime",
+                "repo",
+            ],
+        );
+        map.insert(
+            "stashes",
+            vec![
+                "index",
+                "message",
+                "commit_id",
+                "branch",
+                "author_name",
+                "datetime",
+                "repo",
+            ],
+        );
+        map.insert(
+            "remotes",
+            vec![
+                "name",
+                "fetch_url",
+                "push_url",
+                "fetch_refspecs",
+                "push_refspecs",
+                "tag_mode",
+                "repo",
+            ],
+        );
+        map.insert(
+            "reflog",
+            vec![
+                "ref_name",
+                "index",
+                "old_id",
+                "new_id",
+                "committer_name",
+                "committer_email",
+                "datetime",
+                "action",
+                "message",
+                "repo",
+            ],
+        );
+        map.insert(
+            "status",
+            vec![
+                "path",
+                "index_status",
+                "worktree_status",
+                "is_untracked",
+                "is_ignored",
+                "is_conflicted",
+                "repo",
+            ],
+        );
+        map.insert(
+            "worktrees",
+            vec![
+                "path",
+                "branch",
+                "head_id",
+                "is_locked",
+                "lock_reason",
+                "is_prunable",
+                "is_main",
+                "repo",
+            ],
+        );
+        map.insert(
+            "submodules",
+            vec![
+                "name",
+                "path",
+                "url",
+                "branch",
+                "recorded_commit_id",
+                "checked_out_commit_id",
+                "is_initialized",
+                "is_dirty",
+                "repo",
+            ],
+        );
+        map.insert(
+            "notes",
+            vec!["notes_ref", "commit_id", "note", "note_blob_id", "repo"],
+        );
+        map.insert(
+            "commit_parents",
+            vec!["commit_id", "parent_id", "parent_index", "repo"],
+        );
+        map.insert(
+            "config",
+            vec![
+                "section",
+                "subsection",
+                "key",
+                "value",
+                "scope",
+                "origin_path",
+                "repo",
+            ],
+        );
+        map.insert(
+            "index_entries",
+            vec![
+                "path", "blob_id", "mode", "stage", "size", "mtime", "flags", "repo",
+            ],
+        );
+        map.insert(
+            "objects",
+            vec![
+                "object_id",
+                "kind",
+                "size",
+                "is_packed",
+                "pack_file",
+                "repo",
+            ],
+        );
+        map.insert(
+            "files",
+            vec![
+                "path",
+                "name",
+                "extension",
+                "mode",
+                "size",
+                "blob_id",
+                "is_binary",
                 "repo",
             ],
         );
-        map.insert("tags", vec!["name", "repo"]);
         map
     })
 }
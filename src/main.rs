@@ -2,20 +2,23 @@ use crate::gitql_schema::{tables_fields_names, tables_fields_types};
 // use gitql_cli::printer::csv_printer::CSVPrinter;
 // use gitql_cli::printer::json_printer::JSONPrinter;
 // use crate::nushell_render::render_objects;
-// use nu_path::expand_path_with;
+use nu_path::expand_path_with;
 use nu_plugin::{serve_plugin, MsgPackSerializer, Plugin, PluginCommand};
-use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
-use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Value};
+use nu_plugin::{EngineInterface, EvaluatedCall};
+use nu_protocol::{
+    record, Category, Example, LabeledError, PipelineData, Record, ShellError, Signals, Signature,
+    Span, Spanned, SyntaxShape, Type, Value,
+};
+use std::sync::{Arc, Mutex};
 // use atty::Stream;
+use chrono::{TimeZone, Utc};
 use gitql_cli::{
     // arguments,
     arguments::{Arguments, OutputFormat},
-    diagnostic_reporter,
-    diagnostic_reporter::DiagnosticReporter,
     // render,
 };
 use gitql_core::{environment::Environment, schema::Schema};
-use gitql_data_provider::GitDataProvider;
+use gitql_data_provider::{GitDataProvider, ProviderStats, WalkScope, DEFAULT_NOTES_REF};
 use gitql_engine::{data_provider::DataProvider, engine, engine::EvaluationResult::SelectedGroups};
 use gitql_parser::diagnostic::Diagnostic;
 use gitql_parser::{parser, tokenizer};
@@ -45,7 +48,7 @@ impl Plugin for GitqlPlugin {
 
 pub struct Gitql;
 
-impl SimplePluginCommand for Gitql {
+impl PluginCommand for Gitql {
     type Plugin = GitqlPlugin;
 
     fn name(&self) -> &str {
@@ -54,12 +57,59 @@ impl SimplePluginCommand for Gitql {
 
     fn signature(&self) -> Signature {
         Signature::build(PluginCommand::name(self))
-            // .input_output_types(vec![(Type::Nothing, Type::String)])
-            // --json flag
-            // --csv flag
-            // --debug/analysis flag
-            // --repo folder
-            .required("query", SyntaxShape::String, "gitql query string")
+            .input_output_types(vec![
+                (Type::Nothing, Type::Any),
+                (Type::String, Type::Any),
+                (Type::List(Box::new(Type::String)), Type::Any),
+            ])
+            .optional(
+                "query",
+                SyntaxShape::String,
+                "gitql query string, read from the input when omitted",
+            )
+            .named(
+                "repo",
+                SyntaxShape::OneOf(vec![
+                    SyntaxShape::Filepath,
+                    SyntaxShape::List(Box::new(SyntaxShape::Filepath)),
+                ]),
+                "repository path(s) to query instead of the current directory, can be repeated",
+                Some('r'),
+            )
+            .named(
+                "format",
+                SyntaxShape::String,
+                "output format of the selected rows: records (default), json or csv",
+                Some('f'),
+            )
+            .named(
+                "blame-path",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "repository relative paths to blame, every file at HEAD when omitted",
+                None,
+            )
+            .named(
+                "walk",
+                SyntaxShape::String,
+                "commits walked by the commits and diffs tables: head (default), branches, all, or revspecs such as main..feature or '^v1.0 HEAD'",
+                Some('w'),
+            )
+            .named(
+                "notes-ref",
+                SyntaxShape::String,
+                "notes reference read by the notes table, defaults to refs/notes/commits",
+                None,
+            )
+            .switch(
+                "recurse-submodules",
+                "also query the initialized submodules of every repository, recursively, skipping those that cannot be opened",
+                None,
+            )
+            .switch(
+                "analysis",
+                "return a record with the rows, the duration of each phase and the rows provided per table",
+                Some('a'),
+            )
             .category(Category::Experimental)
     }
 
@@ -79,6 +129,46 @@ impl SimplePluginCommand for Gitql {
                 description: "Show the first 10 refs",
                 result: None,
             },
+            Example {
+                example: "gitql --repo ~/src/nushell --repo ~/src/gitql 'select name, repo from branches'",
+                description: "Show the branches of several repositories at once",
+                result: None,
+            },
+            Example {
+                example: "ls ~/src | where type == dir | get name | gitql 'select name, repo from tags'",
+                description: "Show the tags of every repository piped in",
+                result: None,
+            },
+            Example {
+                example: "'select * from branches' | gitql",
+                description: "Read the query from the input",
+                result: None,
+            },
+            Example {
+                example: "gitql --format json 'select commit_id, datetime, parents_count from commits'",
+                description: "Show the commits as a typed JSON string",
+                result: None,
+            },
+            Example {
+                example: "gitql --analysis 'select count(commit_id) from diffs'",
+                description: "Profile a query and show where the time went",
+                result: None,
+            },
+            Example {
+                example: "gitql 'select path, size from files order by size desc limit 10'",
+                description: "Show the 10 largest files at HEAD",
+                result: None,
+            },
+            Example {
+                example: "gitql --blame-path [src/main.rs] 'select author_name, count(commit_id) from blame group by author_name'",
+                description: "Count the lines of src/main.rs last touched by each author",
+                result: None,
+            },
+            Example {
+                example: "gitql --walk all 'select commit_id, title, ref_name from commits'",
+                description: "Show the commits reachable from any reference and the first reference they were reached from",
+                result: None,
+            },
             Example {
                 example: "gitql 'desribe commits'",
                 description: "Show the data types of the fields in the commits table",
@@ -97,32 +187,35 @@ impl SimplePluginCommand for Gitql {
         _plugin: &GitqlPlugin,
         engine: &EngineInterface,
         call: &EvaluatedCall,
-        _input: &Value,
-    ) -> Result<Value, LabeledError> {
+        input: PipelineData,
+    ) -> Result<PipelineData, LabeledError> {
         let curdir = engine.get_current_dir()?;
-        // let path_to_use = expand_path_with(".", curdir, true);
-        let query_string: String = call.req(0)?;
+        let input = input.into_value(call.head)?;
+        let (query_string, repo_paths) = query_and_repository_paths(call, &input, &curdir)?;
+
+        let output_format = output_format(call)?;
 
         let query_arguments = Arguments {
-            repos: vec![curdir.to_string()],
-            output_format: OutputFormat::Render,
+            repos: repo_paths,
+            output_format,
             pagination: false,
             page_size: 10,
-            analysis: false,
+            analysis: call.has_flag("analysis")?,
             enable_line_editor: false,
         };
 
-        let mut reporter = diagnostic_reporter::DiagnosticReporter::default();
-        let git_repos_result = validate_git_repositories(&query_arguments.repos);
-        if git_repos_result.is_err() {
-            reporter.report_diagnostic(
-                &query_string,
-                Diagnostic::error(git_repos_result.err().unwrap().as_str()),
-            );
-            return Err(LabeledError::new("Invalid repositories paths"));
-        }
+        let mut repos = match validate_git_repositories(&query_arguments.repos) {
+            Ok(repos) => repos,
+            Err(error) => {
+                return Err(
+                    LabeledError::new("Invalid repositories paths").with_label(error, call.head)
+                )
+            }
+        };
 
-        let repos = git_repos_result.ok().unwrap();
+        if call.has_flag("recurse-submodules")? {
+            repos = with_submodule_repositories(repos);
+        }
         let schema = Schema {
             tables_fields_names: tables_fields_names().to_owned(),
             tables_fields_types: tables_fields_types().to_owned(),
@@ -138,13 +231,27 @@ impl SimplePluginCommand for Gitql {
         env.with_standard_functions(&std_signatures, std_functions);
         env.with_aggregation_functions(&aggregation_signatures, aggregation_functions);
 
-        Ok(execute_gitql_query(
+        let blame_paths: Vec<String> = call.get_flag("blame-path")?.unwrap_or_default();
+        let notes_ref: String = call
+            .get_flag("notes-ref")?
+            .unwrap_or_else(|| DEFAULT_NOTES_REF.to_string());
+        let walk = call
+            .get_flag::<String>("walk")?
+            .map_or_else(WalkScope::default, |walk| WalkScope::from_flag(&walk));
+        let provider = GitDataProvider::new(repos)
+            .with_signals(engine.signals().clone())
+            .with_blame_paths(blame_paths)
+            .with_notes_ref(notes_ref)
+            .with_walk(walk);
+
+        execute_gitql_query(
             query_string,
             &query_arguments,
-            &repos,
+            provider,
             &mut env,
-            &mut reporter,
-        ))
+            call.head,
+            engine.signals(),
+        )
 
         // Ok(Value::nothing(call.head))
     }
@@ -161,63 +268,110 @@ fn test_examples() -> Result<(), nu_protocol::ShellError> {
     PluginTest::new("gitql", GitqlPlugin.into())?.test_command_examples(&Gitql)
 }
 
+#[test]
+fn test_gitql_value_to_json() {
+    use gitql_core::values::{
+        boolean::BoolValue, datetime::DateTimeValue, integer::IntValue, null::NullValue,
+        text::TextValue,
+    };
+    use serde_json::json;
+
+    assert_eq!(gitql_value_to_json(&NullValue), json!(null));
+    assert_eq!(gitql_value_to_json(&BoolValue { value: true }), json!(true));
+    assert_eq!(gitql_value_to_json(&IntValue { value: -42 }), json!(-42));
+    assert_eq!(
+        gitql_value_to_json(&TextValue {
+            value: "main".to_string()
+        }),
+        json!("main")
+    );
+    assert_eq!(
+        gitql_value_to_json(&DateTimeValue {
+            value: 1_700_000_000
+        }),
+        json!("2023-11-14T22:13:20+00:00")
+    );
+}
+
+#[test]
+fn test_query_location_span() {
+    // Quoted query: the span covers the quotes around the query text.
+    let query = "select nme from commits";
+    let quoted = Span::new(10, 10 + query.len() + 2);
+    assert_eq!(query_location_span(query, quoted, 7, 10), Span::new(18, 21));
+
+    // A multibyte character before the error shifts the byte offsets.
+    let query = "select 'é' as x, nme";
+    let span = Span::new(0, query.len());
+    assert_eq!(query_location_span(query, span, 17, 20), Span::new(18, 21));
+
+    // Locations past the end of the query fall back to the whole argument.
+    assert_eq!(query_location_span(query, span, 40, 42), span);
+}
+
 fn main() {
     serve_plugin(&GitqlPlugin, MsgPackSerializer);
 }
 
 fn execute_gitql_query(
-    query: String,
+    query: Spanned<String>,
     query_arguments: &Arguments,
-    repos: &[gix::Repository],
+    provider: GitDataProvider,
     env: &mut Environment,
-    reporter: &mut DiagnosticReporter,
-) -> Value {
-    let front_start = std::time::Instant::now();
+    head: Span,
+    signals: &Signals,
+) -> Result<PipelineData, LabeledError> {
+    let Spanned { item: query, span } = query;
+    let tokenize_start = std::time::Instant::now();
     let tokenizer_result = tokenizer::tokenize(query.clone());
     // eprintln!("1");
     if tokenizer_result.is_err() {
         let diagnostic = tokenizer_result.err().unwrap();
-        reporter.report_diagnostic(&query, *diagnostic);
-        return Value::test_string("tokenizer_result error".to_string());
+        return Err(diagnostic_to_labeled_error(&diagnostic, &query, span));
     }
 
     // eprintln!("2");
+    let tokenize_duration = tokenize_start.elapsed();
     let tokens = tokenizer_result.ok().unwrap();
     if tokens.is_empty() {
-        return Value::test_string("No tokens to parse".to_string());
+        return Err(LabeledError::new("No tokens to parse")
+            .with_label("the query is empty", span)
+            .with_help("try 'show tables' to list the tables that can be queried"));
     }
 
     // eprintln!("3");
+    let parse_start = std::time::Instant::now();
     let parser_result = parser::parse_gql(tokens, env);
     if parser_result.is_err() {
         let diagnostic = parser_result.err().unwrap();
-        reporter.report_diagnostic(&query, *diagnostic);
-        return Value::test_string("parser_result error".to_string());
+        return Err(diagnostic_to_labeled_error(&diagnostic, &query, span));
     }
 
     let query_node = parser_result.ok().unwrap();
-    let front_duration = front_start.elapsed();
+    let parse_duration = parse_start.elapsed();
 
     let engine_start = std::time::Instant::now();
-    let provider: Box<dyn DataProvider> = Box::new(GitDataProvider::new(repos.to_vec()));
+    let stats = Arc::new(Mutex::new(ProviderStats::default()));
+    let provider: Box<dyn DataProvider> = Box::new(provider.with_stats(stats.clone()));
     let evaluation_result = engine::evaluate(env, &provider, query_node);
 
     // eprintln!("4");
 
     // Report Runtime exceptions if they exists
     if evaluation_result.is_err() {
-        reporter.report_diagnostic(
-            &query,
-            Diagnostic::exception(&evaluation_result.err().unwrap()),
-        );
-        return Value::test_string("evaluation_result error".to_string());
+        if signals.interrupted() {
+            return Err(ShellError::Interrupted { span: head }.into());
+        }
+
+        let error = evaluation_result.err().unwrap();
+        return Err(LabeledError::new("Failed to evaluate the query").with_label(error, span));
     }
 
     // eprintln!("5");
 
     // Render the result only if they are selected groups not any other statement
     let engine_result = evaluation_result.ok().unwrap();
-    let output: Value = if let SelectedGroups(mut groups) = engine_result {
+    let output: PipelineData = if let SelectedGroups(mut groups) = engine_result {
         // eprintln!("6");
         // eprintln!("{:#?} -> {:#?}", groups.titles, hidden_selection);
         // let hidden_selection: &[String] = &[];
@@ -237,6 +391,8 @@ fn execute_gitql_query(
                     // &hidden_selection,
                     // query_arguments.pagination,
                     // query_arguments.page_size,
+                    head,
+                    signals,
                 )
             }
             OutputFormat::JSON => {
@@ -255,21 +411,22 @@ fn execute_gitql_query(
                     for row in &group.rows {
                         let mut object = serde_json::Map::new();
                         for (i, value) in row.values.iter().enumerate() {
-                            object.insert(
-                                titles[i].to_string(),
-                                serde_json::Value::String(value.literal()),
-                            );
+                            object
+                                .insert(titles[i].to_string(), gitql_value_to_json(value.as_ref()));
                         }
                         elements.push(serde_json::Value::Object(object));
                     }
                 }
 
-                if let Ok(json_str) = serde_json::to_string(&serde_json::Value::Array(elements)) {
+                let json_value = if let Ok(json_str) =
+                    serde_json::to_string(&serde_json::Value::Array(elements))
+                {
                     // println!("{}", json_str);
-                    Value::test_string(json_str)
+                    Value::string(json_str, head)
                 } else {
-                    Value::test_string("No JSON data to show".to_string())
-                }
+                    Value::string("No JSON data to show".to_string(), head)
+                };
+                PipelineData::Value(json_value, None)
             }
             OutputFormat::CSV => {
                 // eprintln!("6.3");
@@ -296,32 +453,274 @@ fn execute_gitql_query(
                     }
                 }
 
-                if let Ok(writer_content) = writer.into_inner() {
+                let csv_value = if let Ok(writer_content) = writer.into_inner() {
                     // println!("{:?}", String::from_utf8(writer_content));
-                    Value::test_string(String::from_utf8(writer_content).unwrap())
+                    Value::string(String::from_utf8(writer_content).unwrap(), head)
                 } else {
-                    Value::test_string("No CSV data to show".to_string())
-                }
+                    Value::string("No CSV data to show".to_string(), head)
+                };
+                PipelineData::Value(csv_value, None)
             }
         }
     } else {
         // eprintln!("7");
 
-        Value::test_string("Not a SelectedGroups result".to_string())
+        PipelineData::Value(
+            Value::string("Not a SelectedGroups result".to_string(), head),
+            None,
+        )
     };
 
     let engine_duration = engine_start.elapsed();
 
     if query_arguments.analysis {
-        eprintln!("\n");
-        eprintln!("Analysis:");
-        eprintln!("Frontend : {:?}", front_duration);
-        eprintln!("Engine   : {:?}", engine_duration);
-        eprintln!("Total    : {:?}", (front_duration + engine_duration));
-        eprintln!("\n");
+        let stats = stats
+            .lock()
+            .map_err(|_| LabeledError::new("Failed to read analysis"))?;
+        let evaluate_duration = engine_duration.saturating_sub(stats.provide_duration);
+        let total_duration = tokenize_duration + parse_duration + engine_duration;
+
+        let mut table_rows = Record::new();
+        for (table, count) in &stats.table_rows {
+            table_rows.push(table, Value::int(*count as i64, head));
+        }
+
+        let duration =
+            |duration: std::time::Duration| Value::duration(duration.as_nanos() as i64, head);
+
+        return Ok(PipelineData::Value(
+            Value::record(
+                record! {
+                    "rows" => output.into_value(head)?,
+                    "tokenize" => duration(tokenize_duration),
+                    "parse" => duration(parse_duration),
+                    "provide" => duration(stats.provide_duration),
+                    "evaluate" => duration(evaluate_duration),
+                    "total" => duration(total_duration),
+                    "table_rows" => Value::record(table_rows, head),
+                },
+                head,
+            ),
+            None,
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Convert a gitql value into a typed JSON value, datetimes are rendered in
+/// ISO-8601 format.
+fn gitql_value_to_json(
+    value: &(dyn gitql_core::values::base::Value + 'static),
+) -> serde_json::Value {
+    if value.is_null() {
+        return serde_json::Value::Null;
+    }
+
+    if let Some(boolean) = value.as_bool() {
+        return serde_json::Value::Bool(boolean);
+    }
+
+    if let Some(integer) = value.as_int() {
+        return serde_json::Value::from(integer);
+    }
+
+    if let Some(float) = value.as_float() {
+        return serde_json::Number::from_f64(float)
+            .map_or(serde_json::Value::Null, serde_json::Value::Number);
+    }
+
+    if let Some(text) = value.as_text() {
+        return serde_json::Value::String(text);
+    }
+
+    if let Some(timestamp) = value.as_date_time() {
+        if let Some(datetime) = Utc.timestamp_opt(timestamp, 0).single() {
+            return serde_json::Value::String(datetime.to_rfc3339());
+        }
+    }
+
+    if let Some(timestamp) = value.as_date() {
+        if let Some(datetime) = Utc.timestamp_opt(timestamp, 0).single() {
+            return serde_json::Value::String(datetime.format("%Y-%m-%d").to_string());
+        }
+    }
+
+    if let Some(array) = value.as_array() {
+        return serde_json::Value::Array(
+            array
+                .iter()
+                .map(|value| gitql_value_to_json(value.as_ref()))
+                .collect(),
+        );
+    }
+
+    serde_json::Value::String(value.literal())
+}
+
+/// Convert a gitql tokenizer or parser diagnostic into a `LabeledError` whose
+/// label points at the reported location inside the query string.
+fn diagnostic_to_labeled_error(diagnostic: &Diagnostic, query: &str, span: Span) -> LabeledError {
+    let label_span = diagnostic
+        .location()
+        .map(|(start, end)| query_location_span(query, span, start, end))
+        .unwrap_or(span);
+
+    let mut error = LabeledError::new("Failed to parse the query")
+        .with_label(diagnostic.message().to_string(), label_span);
+
+    let mut help: Vec<String> = diagnostic.helps().to_vec();
+    help.extend(
+        diagnostic
+            .notes()
+            .iter()
+            .map(|note| format!("note: {note}")),
+    );
+    if let Some(docs) = diagnostic.docs() {
+        help.push(format!("docs: {docs}"));
+    }
+
+    if !help.is_empty() {
+        error = error.with_help(help.join("\n"));
     }
 
-    output
+    error
+}
+
+/// Map a gitql source location (character offsets into the query) onto the span
+/// of the query string argument. The argument span usually includes the
+/// surrounding quotes, which are skipped when the lengths line up. Locations
+/// outside of the query fall back to the whole argument span.
+fn query_location_span(query: &str, span: Span, start: usize, end: usize) -> Span {
+    let quote_offset = match (span.end - span.start).checked_sub(query.len()) {
+        Some(0) => 0,
+        Some(2) => 1,
+        _ => return span,
+    };
+
+    let char_count = query.chars().count();
+    if start >= char_count {
+        return span;
+    }
+
+    let byte_offset = |offset: usize| -> usize {
+        query
+            .char_indices()
+            .nth(offset)
+            .map_or(query.len(), |(byte, _)| byte)
+    };
+
+    let byte_start = byte_offset(start);
+    let byte_end = byte_offset(end.max(start + 1));
+
+    Span::new(
+        span.start + quote_offset + byte_start,
+        span.start + quote_offset + byte_end,
+    )
+}
+
+/// Map the `--format` flag onto a gitql output format, records are rendered as
+/// Nushell values.
+fn output_format(call: &EvaluatedCall) -> Result<OutputFormat, LabeledError> {
+    let Some(format) = call.get_flag::<Spanned<String>>("format")? else {
+        return Ok(OutputFormat::Render);
+    };
+
+    match format.item.to_lowercase().as_str() {
+        "records" => Ok(OutputFormat::Render),
+        "json" => Ok(OutputFormat::JSON),
+        "csv" => Ok(OutputFormat::CSV),
+        _ => Err(LabeledError::new("Invalid output format")
+            .with_label("expected one of records, json or csv", format.span)),
+    }
+}
+
+/// Resolve the query string and the repository paths from the call and its
+/// input. A string input is the query when the positional argument is omitted,
+/// a list input is a list of repository paths.
+fn query_and_repository_paths(
+    call: &EvaluatedCall,
+    input: &Value,
+    curdir: &str,
+) -> Result<(Spanned<String>, Vec<String>), LabeledError> {
+    let query_argument: Option<Spanned<String>> = call.opt(0)?;
+    let mut paths = repository_paths(call, curdir)?;
+
+    let query_string = match input {
+        Value::Nothing { .. } => query_argument,
+        Value::String { val, .. } => match query_argument {
+            Some(query) => {
+                paths.push(expand_repository_path(input, curdir)?);
+                Some(query)
+            }
+            None => Some(Spanned {
+                item: val.clone(),
+                span: input.span(),
+            }),
+        },
+        Value::List { vals, .. } => {
+            for value in vals {
+                paths.push(expand_repository_path(value, curdir)?);
+            }
+            query_argument
+        }
+        other => {
+            return Err(LabeledError::new("Unsupported input").with_label(
+                format!(
+                    "expected a query string or a list of repository paths, found {}",
+                    other.get_type()
+                ),
+                other.span(),
+            ))
+        }
+    };
+
+    let Some(query_string) = query_string else {
+        return Err(LabeledError::new("Missing query")
+            .with_label("pass a query as argument or on the input", call.head));
+    };
+
+    if paths.is_empty() {
+        paths.push(curdir.to_string());
+    }
+
+    Ok((query_string, paths))
+}
+
+/// Collect the values of every `--repo` flag on the call, expanded against the
+/// Nushell current directory.
+fn repository_paths(call: &EvaluatedCall, curdir: &str) -> Result<Vec<String>, LabeledError> {
+    let mut paths: Vec<String> = vec![];
+    for (name, value) in &call.named {
+        if name.item != "repo" {
+            continue;
+        }
+
+        let Some(value) = value else {
+            continue;
+        };
+
+        let values = match value {
+            Value::List { vals, .. } => vals.clone(),
+            other => vec![other.clone()],
+        };
+
+        for value in values {
+            paths.push(expand_repository_path(&value, curdir)?);
+        }
+    }
+
+    Ok(paths)
+}
+
+fn expand_repository_path(value: &Value, curdir: &str) -> Result<String, LabeledError> {
+    let span = value.span();
+    let path = value.clone().coerce_into_string().map_err(|_| {
+        LabeledError::new("Invalid repository path")
+            .with_label("expected a path to a git repository", span)
+    })?;
+    let expanded = expand_path_with(path, curdir, true);
+    Ok(expanded.to_string_lossy().to_string())
 }
 
 fn validate_git_repositories(repositories: &Vec<String>) -> Result<Vec<gix::Repository>, String> {
@@ -335,3 +734,33 @@ fn validate_git_repositories(repositories: &Vec<String>) -> Result<Vec<gix::Repo
     }
     Ok(git_repositories)
 }
+
+/// Append the checked out submodules of every repository, and theirs, after the
+/// repository that contains them. Submodules that cannot be read or opened are
+/// skipped so they do not fail the query for the other repositories.
+fn with_submodule_repositories(repositories: Vec<gix::Repository>) -> Vec<gix::Repository> {
+    let mut git_repositories: Vec<gix::Repository> = vec![];
+    for repository in repositories {
+        let mut submodule_repositories: Vec<gix::Repository> = vec![];
+        collect_submodule_repositories(&repository, &mut submodule_repositories);
+        git_repositories.push(repository);
+        git_repositories.append(&mut submodule_repositories);
+    }
+    git_repositories
+}
+
+fn collect_submodule_repositories(
+    repository: &gix::Repository,
+    git_repositories: &mut Vec<gix::Repository>,
+) {
+    let Ok(Some(submodules)) = repository.submodules() else {
+        return;
+    };
+
+    for submodule in submodules {
+        if let Ok(Some(submodule_repository)) = submodule.open() {
+            git_repositories.push(submodule_repository.clone());
+            collect_submodule_repositories(&submodule_repository, git_repositories);
+        }
+    }
+}
@@ -2,7 +2,7 @@ use chrono::{TimeZone, Utc};
 use gitql_core::object::GitQLObject;
 use gitql_core::object::Row;
 // use gix::config::key;
-use nu_protocol::{Record, Value as NuValue};
+use nu_protocol::{ListStream, PipelineData, Record, Signals, Span, Value as NuValue};
 // use gix::objs::tag;
 
 // enum PaginationInput {
@@ -16,7 +16,9 @@ pub fn render_objects(
     // hidden_selections: &[String],
     // pagination: bool,
     // page_size: usize,
-) -> NuValue {
+    span: Span,
+    signals: &Signals,
+) -> PipelineData {
     // eprintln!(
     //     "pagi: {pagination:#?} - pg: {page_size:#?} - gp_len: {}",
     //     groups.len()
@@ -32,10 +34,13 @@ pub fn render_objects(
     if groups.is_empty() || groups.groups[0].is_empty() {
         // eprintln!("a.1");
 
-        return NuValue::test_string("No data to display".to_string());
+        return PipelineData::Value(
+            NuValue::string("No data to display".to_string(), span),
+            None,
+        );
     }
 
-    let gql_group = groups.groups.first().unwrap();
+    let gql_group = groups.groups.first_mut().unwrap();
     // let gql_group_len = gql_group.len();
     // eprintln!("{gql_group_len:#?}");
     // eprintln!("b");
@@ -53,10 +58,13 @@ pub fn render_objects(
     // if !pagination || page_size >= gql_group_len {
     // eprintln!("d");
 
+    let rows = std::mem::take(&mut gql_group.rows);
     print_group_as_table(
         &groups.titles,
         // table_headers,
-        &gql_group.rows,
+        rows,
+        span,
+        signals,
     )
     // return;
     // } else {
@@ -95,13 +103,14 @@ fn print_group_as_table(
     titles: &[String],
     // table_headers: Vec<comfy_table::Cell>,
     // table_headers: Vec<&String>,
-    rows: &[Row],
-) -> NuValue {
+    rows: Vec<Row>,
+    span: Span,
+    signals: &Signals,
+) -> PipelineData {
     // eprintln!("{table_headers:#?}");
     // eprintln!("{titles:#?}");
 
     // let mut table = comfy_table::Table::new();
-    let mut table = vec![];
     // let mut table_of_values = vec![];
 
     // // Setup table style
@@ -111,214 +120,210 @@ fn print_group_as_table(
 
     // table.set_header(table_headers);
 
-    let titles_len = titles.len();
-    let mut table_row_val: Vec<NuValue> = vec![];
-    // Add rows to the table
-    for row in rows {
-        // let mut table_row: Vec<comfy_table::Cell> = vec![];
-        let mut table_row: Vec<String> = vec![];
-        // let mut table_row_val: Vec<Value> = vec![];
-        for index in 0..titles_len {
-            let value = row.values.get(index).unwrap();
-            // table_row.push(comfy_table::Cell::new(value.to_string()));
-            table_row.push(value.to_string());
-        }
+    // The engine has already built the full result, so the rows are converted
+    // up front and the stream only hands the records out one at a time
+    let records: Vec<NuValue> = rows
+        .into_iter()
+        .map(|row| row_to_record(titles, row, span))
+        .collect();
+
+    // Print table
+    // eprintln!("{table:#?}");
+    // Value::test_list(table_of_values)
+    PipelineData::ListStream(
+        ListStream::new(records.into_iter(), span, signals.clone()),
+        None,
+    )
+}
 
-        let mut rec = Record::new();
-        for index in 0..titles_len {
-            let column_name = &titles[index];
-            let column_value = row.values.get(index).unwrap();
-            // eprintln!("{column_name:#?} - {:#?}", column_value.as_text());
-            // let dt = column_value.data_type();
-            // if let Some(int_val) = column_value.as_any().downcast_ref::<IntValue>() {
-            //     rec.insert(column_name, NuValue::Int(int_val.value));
-            // }
+fn row_to_record(titles: &[String], row: Row, span: Span) -> NuValue {
+    // let mut table_row: Vec<comfy_table::Cell> = vec![];
+    // let mut table_row_val: Vec<Value> = vec![];
 
-            // use super::array::ArrayValue;
-            // use super::boolean::BoolValue;
-            // use super::date::DateValue;
-            // use super::datetime::DateTimeValue;
-            // use super::float::FloatValue;
-            // use super::integer::IntValue;
-            // use super::null::NullValue;
-            // use super::range::RangeValue;
-            // use super::text::TextValue;
-            // use super::time::TimeValue;
-            // }
-            match column_value {
-                v if v.is_array() => {
-                    let array_val = v.as_array().unwrap();
-                    let array_str = array_val
-                        // .values
-                        .iter()
-                        .map(|v| v.literal())
-                        .collect::<Vec<_>>()
-                        .join(",");
-                    rec.insert(column_name, NuValue::test_string(array_str));
-                }
-                v if v.is_bool() => {
-                    rec.insert(column_name, NuValue::test_bool(v.as_bool().unwrap()));
-                }
-                v if v.is_date() => {
-                    rec.insert(
-                        column_name,
-                        NuValue::test_string(v.as_date().unwrap().to_string()),
-                    );
-                }
-                v if v.is_date_time() => {
-                    let timestamp = v.as_date_time().unwrap() * 1_000_000_000;
-                    let dt = Utc.timestamp_nanos(timestamp);
-                    rec.insert(column_name, NuValue::test_date(dt.into()));
-                }
-                v if v.is_float() => {
-                    rec.insert(column_name, NuValue::test_float(v.as_float().unwrap()));
-                }
-                v if v.as_range().is_some() => {
-                    rec.insert(
-                        column_name,
-                        NuValue::test_string(format!(
-                            "{}..{}",
-                            v.as_range().unwrap().0,
-                            v.as_range().unwrap().1
-                        )),
-                    );
-                }
-                v if v.is_null() => {
-                    rec.insert(column_name, NuValue::test_nothing());
-                }
-                v if v.is_int() => {
-                    rec.insert(column_name, NuValue::test_int(v.as_int().unwrap()));
-                }
-                v if v.is_text() => {
-                    rec.insert(column_name, NuValue::test_string(v.as_text().unwrap()));
-                }
-                v if v.is_time() => {
-                    rec.insert(column_name, NuValue::test_string(v.as_time().unwrap()));
-                }
+    let mut rec = Record::new();
+    for index in 0..titles.len() {
+        let column_name = &titles[index];
+        let column_value = row.values.get(index).unwrap();
+        // eprintln!("{column_name:#?} - {:#?}", column_value.as_text());
+        // let dt = column_value.data_type();
+        // if let Some(int_val) = column_value.as_any().downcast_ref::<IntValue>() {
+        //     rec.insert(column_name, NuValue::Int(int_val.value));
+        // }
 
-                _ => {}
+        // use super::array::ArrayValue;
+        // use super::boolean::BoolValue;
+        // use super::date::DateValue;
+        // use super::datetime::DateTimeValue;
+        // use super::float::FloatValue;
+        // use super::integer::IntValue;
+        // use super::null::NullValue;
+        // use super::range::RangeValue;
+        // use super::text::TextValue;
+        // use super::time::TimeValue;
+        // }
+        match column_value {
+            v if v.is_array() => {
+                let array_val = v.as_array().unwrap();
+                let array_str = array_val
+                    // .values
+                    .iter()
+                    .map(|v| v.literal())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                rec.insert(column_name, NuValue::string(array_str, span));
+            }
+            v if v.is_bool() => {
+                rec.insert(column_name, NuValue::bool(v.as_bool().unwrap(), span));
+            }
+            v if v.is_date() => {
+                rec.insert(
+                    column_name,
+                    NuValue::string(v.as_date().unwrap().to_string(), span),
+                );
+            }
+            v if v.is_date_time() => {
+                let timestamp = v.as_date_time().unwrap() * 1_000_000_000;
+                let dt = Utc.timestamp_nanos(timestamp);
+                rec.insert(column_name, NuValue::date(dt.into(), span));
+            }
+            v if v.is_float() => {
+                rec.insert(column_name, NuValue::float(v.as_float().unwrap(), span));
+            }
+            v if v.as_range().is_some() => {
+                rec.insert(
+                    column_name,
+                    NuValue::string(
+                        format!("{}..{}", v.as_range().unwrap().0, v.as_range().unwrap().1),
+                        span,
+                    ),
+                );
+            }
+            v if v.is_null() => {
+                rec.insert(column_name, NuValue::nothing(span));
+            }
+            v if v.is_int() => {
+                rec.insert(column_name, NuValue::int(v.as_int().unwrap(), span));
+            }
+            v if v.is_text() => {
+                rec.insert(column_name, NuValue::string(v.as_text().unwrap(), span));
+            }
+            v if v.is_time() => {
+                rec.insert(column_name, NuValue::string(v.as_time().unwrap(), span));
             }
-            // gitql_core::value::Value::Integer(i) => {
-            //     rec.insert(column_name, Value::test_int(*i));
-            // }
-            // gitql_core::value::Value::Float(f) => {
-            //     rec.insert(column_name, Value::test_float(*f));
-            // }
-            // gitql_core::value::Value::Text(t) => {
-            //     rec.insert(column_name, Value::test_string(t.to_string()));
-            // }
-            // gitql_core::value::Value::Boolean(b) => {
-            //     rec.insert(column_name, Value::test_bool(*b));
-            // }
-            // gitql_core::value::Value::DateTime(dt) => {
-            //     rec.insert(column_name, Value::test_string(dt.to_string()));
-            // }
-            // gitql_core::value::Value::Date(dt) => {
-            //     rec.insert(column_name, Value::test_string(dt.to_string()));
-            // }
-            // gitql_core::value::Value::Time(t) => {
-            //     rec.insert(column_name, Value::test_string(t.to_string()));
-            // }
-            // // gitql_core::value::Value::Array(a, b) => {
-            // //     rec.insert(
-            // //         column_name,
-            // //         Value::test_list(
-            // //             b.iter()
-            // //                 .map(|v| Value::test_string(v.to_string()))
-            // //                 .collect(),
-            // //         ),
-            // //     );
-            // // }
-            // gitql_core::value::Value::Array(a, b) => {
-            //     rec.insert(
-            //         column_name,
-            //         match a {
-            //             gitql_core::types::DataType::Text => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Integer => Value::test_list(
-            //                 b.iter().map(|v| Value::test_int(v.as_int())).collect(),
-            //             ),
-            //             gitql_core::types::DataType::Float => Value::test_list(
-            //                 b.iter().map(|v| Value::test_float(v.as_float())).collect(),
-            //             ),
-            //             gitql_core::types::DataType::Boolean => Value::test_list(
-            //                 b.iter().map(|v| Value::test_bool(v.as_bool())).collect(),
-            //             ),
-            //             gitql_core::types::DataType::Date => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Time => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::DateTime => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Array(_a) => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Range(_r) => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Variant(_vv) => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Optional(_o) => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Varargs(_v) => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Dynamic(_d) => Value::test_list(
-            //                 b.iter()
-            //                     .map(|v| Value::test_string(v.to_string()))
-            //                     .collect(),
-            //             ),
-            //             gitql_core::types::DataType::Undefined => todo!(),
-            //             gitql_core::types::DataType::Any => todo!(),
-            //             gitql_core::types::DataType::Null => todo!(),
-            //         }, // Value::test_list(
-            //            //     b.iter()
-            //            //         .map(|v| Value::test_string(v.to_string()))
-            //            //         .collect(),
-            //            // ),
-            //     );
-            // }
 
-            // gitql_core::value::Value::Range(a, b, c) => todo!(),
-            // gitql_core::value::Value::Null => todo!(),
-            // }
-            // rec.insert(column_name, Value::test_string(column_value));
+            _ => {}
         }
-        table_row_val.push(NuValue::test_record(rec));
+        // gitql_core::value::Value::Integer(i) => {
+        //     rec.insert(column_name, Value::test_int(*i));
+        // }
+        // gitql_core::value::Value::Float(f) => {
+        //     rec.insert(column_name, Value::test_float(*f));
+        // }
+        // gitql_core::value::Value::Text(t) => {
+        //     rec.insert(column_name, Value::test_string(t.to_string()));
+        // }
+        // gitql_core::value::Value::Boolean(b) => {
+        //     rec.insert(column_name, Value::test_bool(*b));
+        // }
+        // gitql_core::value::Value::DateTime(dt) => {
+        //     rec.insert(column_name, Value::test_string(dt.to_string()));
+        // }
+        // gitql_core::value::Value::Date(dt) => {
+        //     rec.insert(column_name, Value::test_string(dt.to_string()));
+        // }
+        // gitql_core::value::Value::Time(t) => {
+        //     rec.insert(column_name, Value::test_string(t.to_string()));
+        // }
+        // // gitql_core::value::Value::Array(a, b) => {
+        // //     rec.insert(
+        // //         column_name,
+        // //         Value::test_list(
+        // //             b.iter()
+        // //                 .map(|v| Value::test_string(v.to_string()))
+        // //                 .collect(),
+        // //         ),
+        // //     );
+        // // }
+        // gitql_core::value::Value::Array(a, b) => {
+        //     rec.insert(
+        //         column_name,
+        //         match a {
+        //             gitql_core::types::DataType::Text => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Integer => Value::test_list(
+        //                 b.iter().map(|v| Value::test_int(v.as_int())).collect(),
+        //             ),
+        //             gitql_core::types::DataType::Float => Value::test_list(
+        //                 b.iter().map(|v| Value::test_float(v.as_float())).collect(),
+        //             ),
+        //             gitql_core::types::DataType::Boolean => Value::test_list(
+        //                 b.iter().map(|v| Value::test_bool(v.as_bool())).collect(),
+        //             ),
+        //             gitql_core::types::DataType::Date => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Time => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::DateTime => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Array(_a) => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Range(_r) => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Variant(_vv) => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Optional(_o) => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Varargs(_v) => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Dynamic(_d) => Value::test_list(
+        //                 b.iter()
+        //                     .map(|v| Value::test_string(v.to_string()))
+        //                     .collect(),
+        //             ),
+        //             gitql_core::types::DataType::Undefined => todo!(),
+        //             gitql_core::types::DataType::Any => todo!(),
+        //             gitql_core::types::DataType::Null => todo!(),
+        //         }, // Value::test_list(
+        //            //     b.iter()
+        //            //         .map(|v| Value::test_string(v.to_string()))
+        //            //         .collect(),
+        //            // ),
+        //     );
+        // }
 
-        // table.add_row(table_row);
-        table.push(table_row);
-        // table_of_values.push(Value::test_list(table_row_val))
+        // gitql_core::value::Value::Range(a, b, c) => todo!(),
+        // gitql_core::value::Value::Null => todo!(),
+        // }
+        // rec.insert(column_name, Value::test_string(column_value));
     }
-
-    // Print table
-    // eprintln!("{table:#?}");
-    // Value::test_list(table_of_values)
-    NuValue::test_list(table_row_val)
+    NuValue::record(rec, span)
 }
 
 // fn handle_pagination_input(current_page: usize, number_of_pages: usize) -> PaginationInput {